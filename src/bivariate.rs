@@ -0,0 +1,348 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Statistics of paired values: covariance and
+//! correlation.
+
+use crate::error::*;
+use crate::nan::*;
+use crate::num::*;
+
+/// Online accumulator for the means, variances and
+/// covariance of a stream of pairs of numbers. Uses the
+/// bivariate form of Welford's update, which needs only
+/// constant memory. Accumulators built over separate parts
+/// of the input can be combined exactly with
+/// [`merge`](CoMoments::merge). A pair is NaN if either of
+/// its values is.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let mut c = CoMoments::new();
+/// for &(x, y) in &[(1.0, 2.0), (2.0, 4.0), (3.0, 7.0)] {
+///     c.push(x, y);
+/// }
+/// assert_eq!(3, c.count());
+/// assert_eq!(Ok(2.5), c.covariance(1));
+/// ```
+/// ```
+/// # use stats::*;
+/// let c: CoMoments = [(1.0, 3.0), (2.0, 1.0), (3.0, -1.0)].iter().collect();
+/// assert_eq!(Ok(-1.0), c.pearson());
+/// assert_eq!((Ok(2.0), Ok(1.0)), (c.mean_x(), c.mean_y()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoMoments {
+    count: u64,
+    mean_x: f64,
+    mean_y: f64,
+    m2_x: f64,
+    m2_y: f64,
+    c_xy: f64,
+    nans: u64,
+    nan: NanPolicy,
+}
+
+impl Default for CoMoments {
+    fn default() -> Self {
+        CoMoments {
+            count: 0,
+            mean_x: 0.0,
+            mean_y: 0.0,
+            m2_x: 0.0,
+            m2_y: 0.0,
+            c_xy: 0.0,
+            nans: 0,
+            nan: NanPolicy::default(),
+        }
+    }
+}
+
+impl CoMoments {
+    /// Make a new empty accumulator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make a new empty accumulator that treats NaNs
+    /// according to `nan`.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use stats::*;
+    /// let mut c = CoMoments::with_nan_policy(NanPolicy::Skip);
+    /// c.extend(&[(1.0, 1.0), (2.0, std::f64::NAN), (3.0, 2.0)]);
+    /// assert_eq!(Ok(1.0), c.covariance(1));
+    /// ```
+    pub fn with_nan_policy(nan: NanPolicy) -> Self {
+        CoMoments {
+            nan,
+            ..Self::default()
+        }
+    }
+
+    /// Add a pair of values to the accumulator.
+    pub fn push(&mut self, x: f64, y: f64) {
+        if x.is_nan() || y.is_nan() {
+            self.nans += 1;
+            return;
+        }
+        self.count += 1;
+        let n = self.count as f64;
+        let dx = x - self.mean_x;
+        let dy = y - self.mean_y;
+        self.mean_x += dx / n;
+        self.mean_y += dy / n;
+        self.m2_x += dx * (x - self.mean_x);
+        self.m2_y += dy * (y - self.mean_y);
+        self.c_xy += dx * (y - self.mean_y);
+    }
+
+    /// Combine the pairs accumulated by `other` into this
+    /// accumulator, as if they had been pushed here.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use stats::*;
+    /// let pairs = [(1.0, 2.0), (2.0, 4.0), (3.0, 7.0), (4.0, 7.0)];
+    /// let mut c: CoMoments = pairs[..1].iter().collect();
+    /// c.merge(&pairs[1..].iter().collect());
+    /// let all: CoMoments = pairs.iter().collect();
+    /// assert_eq!(all.covariance(1), c.covariance(1));
+    /// ```
+    pub fn merge(&mut self, other: &Self) {
+        self.nans += other.nans;
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = CoMoments {
+                nans: self.nans,
+                nan: self.nan,
+                ..*other
+            };
+            return;
+        }
+        let n1 = self.count as f64;
+        let n2 = other.count as f64;
+        let count = self.count + other.count;
+        let n = count as f64;
+        let dx = other.mean_x - self.mean_x;
+        let dy = other.mean_y - self.mean_y;
+        let w = n1 * n2 / n;
+        self.mean_x += dx * (n2 / n);
+        self.mean_y += dy * (n2 / n);
+        self.m2_x += other.m2_x + dx * dx * w;
+        self.m2_y += other.m2_y + dy * dy * w;
+        self.c_xy += other.c_xy + dx * dy * w;
+        self.count = count;
+    }
+
+    /// Number of pairs accumulated so far.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Number of NaN pairs pushed so far. They are handled
+    /// according to the accumulator's [`NanPolicy`] when a
+    /// statistic is requested.
+    pub fn nans(&self) -> u64 {
+        self.nans
+    }
+
+    /// Apply the NaN policy, check that at least `needed`
+    /// pairs have been accumulated, then compute a statistic
+    /// with `f`.
+    fn checked<F>(&self, needed: u64, f: F) -> StatResult
+    where
+        F: FnOnce() -> StatResult,
+    {
+        if self.nans > 0 {
+            match self.nan {
+                NanPolicy::Propagate => return Ok(f64::NAN),
+                NanPolicy::Error => return Err(StatError::NanInput),
+                NanPolicy::Skip => (),
+            }
+        }
+        if self.count == 0 {
+            Err(StatError::Empty)
+        } else if self.count < needed {
+            Err(StatError::TooFewSamples {
+                needed: needed as usize,
+                got: self.count as usize,
+            })
+        } else {
+            f()
+        }
+    }
+
+    /// Arithmetic mean of the first values of the pairs.
+    pub fn mean_x(&self) -> StatResult {
+        self.checked(1, || finite(self.mean_x))
+    }
+
+    /// Arithmetic mean of the second values of the pairs.
+    pub fn mean_y(&self) -> StatResult {
+        self.checked(1, || finite(self.mean_y))
+    }
+
+    /// Variance of the first values of the pairs, with
+    /// `ddof` delta degrees of freedom as for
+    /// [`Moments::variance`](crate::Moments::variance).
+    pub fn variance_x(&self, ddof: usize) -> StatResult {
+        let dof = self.count as f64 - ddof as f64;
+        self.checked(ddof as u64 + 1, || finite(self.m2_x / dof))
+    }
+
+    /// Variance of the second values of the pairs, with
+    /// `ddof` delta degrees of freedom.
+    pub fn variance_y(&self, ddof: usize) -> StatResult {
+        let dof = self.count as f64 - ddof as f64;
+        self.checked(ddof as u64 + 1, || finite(self.m2_y / dof))
+    }
+
+    /// Covariance of the pairs accumulated so far, with
+    /// `ddof` delta degrees of freedom: the sum of the
+    /// products of the deviations from the means is divided
+    /// by `n - ddof`. Use 0 for the population covariance
+    /// and 1 for the sample covariance.
+    pub fn covariance(&self, ddof: usize) -> StatResult {
+        let dof = self.count as f64 - ddof as f64;
+        self.checked(ddof as u64 + 1, || finite(self.c_xy / dof))
+    }
+
+    /// Pearson correlation coefficient of the pairs
+    /// accumulated so far, from -1 to 1. The correlation is
+    /// undefined if either value is constant.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use stats::*;
+    /// let c: CoMoments = [(1.0, 3.0), (2.0, 3.0)].iter().collect();
+    /// assert_eq!(Err(StatError::ZeroVariance), c.pearson());
+    /// ```
+    pub fn pearson(&self) -> StatResult {
+        self.checked(2, || {
+            if self.m2_x == 0.0 || self.m2_y == 0.0 {
+                return Err(StatError::ZeroVariance);
+            }
+            let r = self.c_xy / (self.m2_x * self.m2_y).sqrt();
+            // Roundoff may push the ratio just outside
+            // [-1, 1].
+            finite(r).map(|r| r.clamp(-1.0, 1.0))
+        })
+    }
+}
+
+impl Extend<(f64, f64)> for CoMoments {
+    fn extend<I: IntoIterator<Item = (f64, f64)>>(&mut self, iter: I) {
+        for (x, y) in iter {
+            self.push(x, y);
+        }
+    }
+}
+
+impl<'a> Extend<&'a (f64, f64)> for CoMoments {
+    fn extend<I: IntoIterator<Item = &'a (f64, f64)>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
+impl std::iter::FromIterator<(f64, f64)> for CoMoments {
+    fn from_iter<I: IntoIterator<Item = (f64, f64)>>(iter: I) -> Self {
+        let mut c = CoMoments::new();
+        c.extend(iter);
+        c
+    }
+}
+
+impl<'a> std::iter::FromIterator<&'a (f64, f64)> for CoMoments {
+    fn from_iter<I: IntoIterator<Item = &'a (f64, f64)>>(iter: I) -> Self {
+        iter.into_iter().cloned().collect()
+    }
+}
+
+/// Accumulate the paired values `xs` and `ys`, which must
+/// have the same length, under the NaN policy `nan`.
+fn co_moments<T: Numeric, U: Numeric>(xs: &[T], ys: &[U], nan: NanPolicy) -> StatResult<CoMoments> {
+    if xs.len() != ys.len() {
+        return Err(StatError::LengthMismatch {
+            left: xs.len(),
+            right: ys.len(),
+        });
+    }
+    let mut c = CoMoments::with_nan_policy(nan);
+    c.extend(
+        xs.iter()
+            .map(|x| x.to_f64())
+            .zip(ys.iter().map(|y| y.to_f64())),
+    );
+    Ok(c)
+}
+
+/// Covariance of the paired values `xs` and `ys`, with
+/// `ddof` delta degrees of freedom as for
+/// [`CoMoments::covariance`]. The inputs must have the same
+/// length. NaN handling applies to the pairs.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let xs = [1.0, 2.0, 3.0];
+/// let ys = [2.0, 4.0, 7.0];
+/// assert_eq!(Ok(2.5), covariance(&xs, &ys, 1, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let xs = [1.0, 2.0, 3.0];
+/// assert_eq!(variance(&xs, 0), covariance(&xs, &xs, 0, NanPolicy::Error).ok());
+/// ```
+/// ```
+/// # use stats::*;
+/// let err = StatError::LengthMismatch { left: 2, right: 1 };
+/// assert_eq!(Err(err), covariance(&[1.0, 2.0], &[1.0], 1, NanPolicy::Error));
+/// ```
+pub fn covariance<T: Numeric, U: Numeric>(
+    xs: &[T],
+    ys: &[U],
+    ddof: usize,
+    nan: NanPolicy,
+) -> StatResult {
+    co_moments(xs, ys, nan)?.covariance(ddof)
+}
+
+/// Pearson correlation coefficient of the paired values
+/// `xs` and `ys`, from -1 to 1, as for
+/// [`CoMoments::pearson`]. The inputs must have the same
+/// length. NaN handling applies to the pairs.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let xs = [1.0, 2.0, 3.0, 4.0];
+/// let ys = [10.0, 20.0, 30.0, 40.0];
+/// assert_eq!(Ok(1.0), pearson(&xs, &ys, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let xs = [1.0, 2.0, 3.0, 4.0, 5.0];
+/// let ys = [2.0, 1.0, 4.0, 3.0, 5.0];
+/// assert_eq!(Ok(0.8), pearson(&xs, &ys, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let err = StatError::TooFewSamples { needed: 2, got: 1 };
+/// assert_eq!(Err(err), pearson(&[1.0], &[1.0], NanPolicy::Error));
+/// ```
+pub fn pearson<T: Numeric, U: Numeric>(xs: &[T], ys: &[U], nan: NanPolicy) -> StatResult {
+    co_moments(xs, ys, nan)?.pearson()
+}
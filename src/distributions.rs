@@ -0,0 +1,926 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Probability distributions, with their distribution
+//! functions, quantiles and moments.
+
+use std::f64::consts::PI;
+
+use crate::error::*;
+use crate::special::*;
+
+/// A probability distribution on the real line. Functions
+/// taking a probability return NaN if it is not between 0
+/// and 1.
+pub trait Distribution {
+    /// Cumulative distribution function: the probability of
+    /// a value at most `x`.
+    fn cdf(&self, x: f64) -> f64;
+
+    /// Survival function: the probability of a value
+    /// greater than `x`, `1 - cdf(x)`. Distributions
+    /// compute it directly where that is more accurate in
+    /// the upper tail.
+    fn sf(&self, x: f64) -> f64 {
+        1.0 - self.cdf(x)
+    }
+
+    /// Quantile function: the smallest `x` with `cdf(x)` at
+    /// least `p`.
+    fn inverse_cdf(&self, p: f64) -> f64;
+
+    /// Mean of the distribution, or NaN if it is undefined.
+    fn mean(&self) -> f64;
+
+    /// Variance of the distribution, infinite or NaN if it
+    /// is unbounded or undefined.
+    fn variance(&self) -> f64;
+}
+
+/// A distribution with a probability density.
+pub trait Continuous: Distribution {
+    /// Probability density function at `x`.
+    fn pdf(&self, x: f64) -> f64;
+}
+
+/// A distribution over the nonnegative integers.
+pub trait Discrete: Distribution {
+    /// Probability mass function: the probability of the
+    /// value `k`.
+    fn pmf(&self, k: u64) -> f64;
+}
+
+/// `a ln(x)`, taken to be 0 when `a` is 0 so that densities
+/// are right at the ends of their supports.
+fn xlnx(a: f64, x: f64) -> f64 {
+    if a == 0.0 {
+        0.0
+    } else {
+        a * x.ln()
+    }
+}
+
+/// `a ln(1 + x)`, taken to be 0 when `a` is 0.
+fn xln1p(a: f64, x: f64) -> f64 {
+    if a == 0.0 {
+        0.0
+    } else {
+        a * x.ln_1p()
+    }
+}
+
+/// Check that `p` is a probability.
+fn is_probability(p: f64) -> bool {
+    (0.0..=1.0).contains(&p)
+}
+
+/// Check that a distribution parameter is positive and
+/// finite.
+fn check_positive(x: f64, what: &'static str) -> StatResult<()> {
+    if x > 0.0 && x.is_finite() {
+        Ok(())
+    } else {
+        Err(StatError::InvalidParameter(what))
+    }
+}
+
+/// Bisection steps after which [`invert`] gives up: more
+/// than enough to narrow `-f64::MAX..f64::MAX` down to
+/// adjacent floats.
+const MAX_BISECTIONS: usize = 2200;
+
+/// Quantile `p` of a continuous distribution supported on
+/// `lo..hi`, by bracketing and bisection. The distribution
+/// function is used for lower quantiles and the survival
+/// function for upper ones, so that both tails are found
+/// accurately. A quantile beyond `f64::MAX` is infinite.
+fn invert<D: Distribution + ?Sized>(dist: &D, p: f64, lo: f64, hi: f64) -> f64 {
+    if !is_probability(p) {
+        return f64::NAN;
+    }
+    if p == 0.0 {
+        return lo;
+    }
+    if p == 1.0 {
+        return hi;
+    }
+    let below = |x: f64| {
+        if p <= 0.5 {
+            dist.cdf(x) < p
+        } else {
+            dist.sf(x) > 1.0 - p
+        }
+    };
+    let (mut lo, mut hi) = (lo, hi);
+    if lo == f64::NEG_INFINITY {
+        lo = -1.0;
+        while !below(lo) {
+            if lo == -f64::MAX {
+                return f64::NEG_INFINITY;
+            }
+            lo = (2.0 * lo).max(-f64::MAX);
+        }
+    }
+    if hi == f64::INFINITY {
+        hi = lo.max(0.0) + 1.0;
+        while below(hi) {
+            if hi == f64::MAX {
+                return f64::INFINITY;
+            }
+            hi = (2.0 * hi).min(f64::MAX);
+        }
+    }
+    for _ in 0..MAX_BISECTIONS {
+        let mid = 0.5 * lo + 0.5 * hi;
+        if !mid.is_finite() || mid <= lo || mid >= hi {
+            break;
+        }
+        if below(mid) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    hi
+}
+
+/// Quantile `p` of a discrete distribution supported on
+/// `0..=max`: the smallest `k` with `cdf(k)` at least `p`,
+/// by bracketing and binary search.
+fn invert_discrete<D: Distribution + ?Sized>(dist: &D, p: f64, max: f64) -> f64 {
+    if !is_probability(p) {
+        return f64::NAN;
+    }
+    if p == 1.0 {
+        return max;
+    }
+    let below = |k: f64| {
+        if p <= 0.5 {
+            dist.cdf(k) < p
+        } else {
+            dist.sf(k) > 1.0 - p
+        }
+    };
+    if !below(0.0) {
+        return 0.0;
+    }
+    let max = max.min(f64::MAX);
+    let (mut lo, mut hi) = (0.0, 1.0f64);
+    while hi < max && below(hi) {
+        lo = hi;
+        hi = (2.0 * hi).min(max);
+    }
+    // Now lo is below the quantile and hi is not.
+    while hi - lo > 1.0 {
+        let mid = (0.5 * lo + 0.5 * hi).floor();
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        if below(mid) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    hi
+}
+
+/// The normal (Gaussian) distribution with the given mean
+/// and standard deviation.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let z = Normal::standard();
+/// assert!((z.cdf(1.96) - 0.9750021048517795).abs() < 1e-15);
+/// assert!((z.inverse_cdf(0.975) - 1.959963984540054).abs() < 1e-15);
+/// assert!((z.sf(10.0) / 7.619853024160593e-24 - 1.0).abs() < 1e-13);
+/// assert!((z.pdf(0.0) - 0.3989422804014327).abs() < 1e-15);
+/// ```
+/// ```
+/// # use stats::*;
+/// let n = Normal::new(10.0, 2.0).unwrap();
+/// assert_eq!(0.5, n.cdf(10.0));
+/// assert_eq!((10.0, 4.0), (n.mean(), n.variance()));
+/// assert!(Normal::new(0.0, 0.0).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Normal {
+    mean: f64,
+    sd: f64,
+}
+
+impl Normal {
+    /// Make a normal distribution with mean `mean` and
+    /// positive standard deviation `sd`.
+    pub fn new(mean: f64, sd: f64) -> StatResult<Self> {
+        if !mean.is_finite() {
+            return Err(StatError::InvalidParameter("mean must be finite"));
+        }
+        check_positive(sd, "standard deviation must be positive")?;
+        Ok(Normal { mean, sd })
+    }
+
+    /// The standard normal distribution, with mean 0 and
+    /// standard deviation 1.
+    pub fn standard() -> Self {
+        Normal { mean: 0.0, sd: 1.0 }
+    }
+
+    /// The value `x` scaled for the standard error function.
+    fn scaled(&self, x: f64) -> f64 {
+        (x - self.mean) / (self.sd * std::f64::consts::SQRT_2)
+    }
+}
+
+impl Distribution for Normal {
+    fn cdf(&self, x: f64) -> f64 {
+        0.5 * erfc(-self.scaled(x))
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        0.5 * erfc(self.scaled(x))
+    }
+
+    fn inverse_cdf(&self, p: f64) -> f64 {
+        self.mean - self.sd * std::f64::consts::SQRT_2 * erfc_inv(2.0 * p)
+    }
+
+    fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    fn variance(&self) -> f64 {
+        self.sd * self.sd
+    }
+}
+
+impl Continuous for Normal {
+    fn pdf(&self, x: f64) -> f64 {
+        let z = (x - self.mean) / self.sd;
+        (-0.5 * z * z).exp() / (self.sd * (2.0 * PI).sqrt())
+    }
+}
+
+/// Student's t distribution with the given degrees of
+/// freedom: the distribution of the mean of normal samples
+/// standardized by their sample standard deviation.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let t = StudentsT::new(3.0).unwrap();
+/// assert!((t.cdf(2.0) - 0.9303370157205785).abs() < 1e-15);
+/// assert!((t.inverse_cdf(0.975) - 3.1824463052837096).abs() < 1e-14);
+/// assert!((t.inverse_cdf(0.025) + 3.1824463052837096).abs() < 1e-14);
+/// assert_eq!(3.0, t.variance());
+/// ```
+/// ```
+/// # use stats::*;
+/// let t = StudentsT::new(1.0).unwrap();
+/// assert!((t.pdf(1.0) - 0.5 / std::f64::consts::PI).abs() < 1e-15);
+/// assert!(t.mean().is_nan());
+/// assert!(t.inverse_cdf(1e-310) < -1e150);
+/// assert!(t.inverse_cdf(1.0 - 1e-16) > 1e15);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StudentsT {
+    df: f64,
+}
+
+impl StudentsT {
+    /// Make a t distribution with `df` positive degrees of
+    /// freedom, which need not be an integer.
+    pub fn new(df: f64) -> StatResult<Self> {
+        check_positive(df, "degrees of freedom must be positive")?;
+        Ok(StudentsT { df })
+    }
+
+    /// Probability of a value beyond `|t|` in one tail.
+    fn tail(&self, t: f64) -> f64 {
+        0.5 * regularized_beta(0.5 * self.df, 0.5, self.df / (self.df + t * t))
+    }
+}
+
+impl Distribution for StudentsT {
+    fn cdf(&self, t: f64) -> f64 {
+        if t.is_nan() {
+            f64::NAN
+        } else if t < 0.0 {
+            self.tail(t)
+        } else {
+            1.0 - self.tail(t)
+        }
+    }
+
+    fn sf(&self, t: f64) -> f64 {
+        self.cdf(-t)
+    }
+
+    fn inverse_cdf(&self, p: f64) -> f64 {
+        invert(self, p, f64::NEG_INFINITY, f64::INFINITY)
+    }
+
+    fn mean(&self) -> f64 {
+        if self.df > 1.0 {
+            0.0
+        } else {
+            f64::NAN
+        }
+    }
+
+    fn variance(&self) -> f64 {
+        if self.df > 2.0 {
+            self.df / (self.df - 2.0)
+        } else if self.df > 1.0 {
+            f64::INFINITY
+        } else {
+            f64::NAN
+        }
+    }
+}
+
+impl Continuous for StudentsT {
+    fn pdf(&self, t: f64) -> f64 {
+        let nu = self.df;
+        (ln_gamma(0.5 * (nu + 1.0))
+            - ln_gamma(0.5 * nu)
+            - 0.5 * (nu * PI).ln()
+            - xln1p(0.5 * (nu + 1.0), t * t / nu))
+        .exp()
+    }
+}
+
+/// The gamma distribution with the given shape and scale.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let g = Gamma::new(2.0, 1.0).unwrap();
+/// assert!((g.cdf(3.0) - (1.0 - 4.0 * (-3f64).exp())).abs() < 1e-15);
+/// assert!((g.pdf(3.0) - 3.0 * (-3f64).exp()).abs() < 1e-15);
+/// assert!((g.cdf(g.inverse_cdf(0.3)) - 0.3).abs() < 1e-15);
+/// assert_eq!((2.0, 2.0), (g.mean(), g.variance()));
+/// assert!(g.cdf(f64::NAN).is_nan() && g.sf(f64::NAN).is_nan());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gamma {
+    shape: f64,
+    scale: f64,
+}
+
+impl Gamma {
+    /// Make a gamma distribution with positive `shape` and
+    /// `scale`.
+    pub fn new(shape: f64, scale: f64) -> StatResult<Self> {
+        check_positive(shape, "shape must be positive")?;
+        check_positive(scale, "scale must be positive")?;
+        Ok(Gamma { shape, scale })
+    }
+}
+
+impl Distribution for Gamma {
+    fn cdf(&self, x: f64) -> f64 {
+        if x <= 0.0 {
+            return 0.0;
+        }
+        regularized_gamma_p(self.shape, x / self.scale)
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        if x <= 0.0 {
+            return 1.0;
+        }
+        regularized_gamma_q(self.shape, x / self.scale)
+    }
+
+    fn inverse_cdf(&self, p: f64) -> f64 {
+        invert(self, p, 0.0, f64::INFINITY)
+    }
+
+    fn mean(&self) -> f64 {
+        self.shape * self.scale
+    }
+
+    fn variance(&self) -> f64 {
+        self.shape * self.scale * self.scale
+    }
+}
+
+impl Continuous for Gamma {
+    fn pdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            return 0.0;
+        }
+        let x = x / self.scale;
+        (xlnx(self.shape - 1.0, x) - x - ln_gamma(self.shape)).exp() / self.scale
+    }
+}
+
+/// The chi-squared distribution with the given degrees of
+/// freedom: the distribution of the sum of the squares of
+/// that many standard normal values.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let c = ChiSquared::new(2.0).unwrap();
+/// assert!((c.cdf(1.0) - (1.0 - (-0.5f64).exp())).abs() < 1e-15);
+/// assert!((c.inverse_cdf(0.95) - 5.991464547107979).abs() < 1e-14);
+/// assert_eq!(0.5, c.pdf(0.0));
+/// assert_eq!((2.0, 4.0), (c.mean(), c.variance()));
+/// assert!(c.cdf(f64::NAN).is_nan() && c.sf(f64::NAN).is_nan());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChiSquared {
+    gamma: Gamma,
+}
+
+impl ChiSquared {
+    /// Make a chi-squared distribution with `df` positive
+    /// degrees of freedom.
+    pub fn new(df: f64) -> StatResult<Self> {
+        check_positive(df, "degrees of freedom must be positive")?;
+        Ok(ChiSquared {
+            gamma: Gamma {
+                shape: 0.5 * df,
+                scale: 2.0,
+            },
+        })
+    }
+}
+
+impl Distribution for ChiSquared {
+    fn cdf(&self, x: f64) -> f64 {
+        self.gamma.cdf(x)
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        self.gamma.sf(x)
+    }
+
+    fn inverse_cdf(&self, p: f64) -> f64 {
+        self.gamma.inverse_cdf(p)
+    }
+
+    fn mean(&self) -> f64 {
+        self.gamma.mean()
+    }
+
+    fn variance(&self) -> f64 {
+        self.gamma.variance()
+    }
+}
+
+impl Continuous for ChiSquared {
+    fn pdf(&self, x: f64) -> f64 {
+        self.gamma.pdf(x)
+    }
+}
+
+/// The F distribution with the given numerator and
+/// denominator degrees of freedom: the distribution of the
+/// ratio of independent chi-squared values, each divided by
+/// its degrees of freedom.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let f = FisherF::new(2.0, 4.0).unwrap();
+/// assert!((f.cdf(1.0) - 5.0 / 9.0).abs() < 1e-15);
+/// assert!((f.sf(1.0) - 4.0 / 9.0).abs() < 1e-15);
+/// assert!((f.pdf(1.0) - 8.0 / 27.0).abs() < 1e-15);
+/// assert_eq!(2.0, f.mean());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FisherF {
+    df1: f64,
+    df2: f64,
+}
+
+impl FisherF {
+    /// Make an F distribution with `df1` and `df2` positive
+    /// degrees of freedom for the numerator and denominator.
+    pub fn new(df1: f64, df2: f64) -> StatResult<Self> {
+        check_positive(df1, "degrees of freedom must be positive")?;
+        check_positive(df2, "degrees of freedom must be positive")?;
+        Ok(FisherF { df1, df2 })
+    }
+}
+
+impl Distribution for FisherF {
+    fn cdf(&self, x: f64) -> f64 {
+        if x <= 0.0 {
+            return 0.0;
+        }
+        let (d1, d2) = (self.df1, self.df2);
+        regularized_beta(0.5 * d1, 0.5 * d2, d1 * x / (d1 * x + d2))
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        if x <= 0.0 {
+            return 1.0;
+        }
+        let (d1, d2) = (self.df1, self.df2);
+        regularized_beta(0.5 * d2, 0.5 * d1, d2 / (d1 * x + d2))
+    }
+
+    fn inverse_cdf(&self, p: f64) -> f64 {
+        invert(self, p, 0.0, f64::INFINITY)
+    }
+
+    fn mean(&self) -> f64 {
+        if self.df2 > 2.0 {
+            self.df2 / (self.df2 - 2.0)
+        } else {
+            f64::NAN
+        }
+    }
+
+    fn variance(&self) -> f64 {
+        let (d1, d2) = (self.df1, self.df2);
+        if d2 > 4.0 {
+            2.0 * d2 * d2 * (d1 + d2 - 2.0) / (d1 * (d2 - 2.0).powi(2) * (d2 - 4.0))
+        } else if d2 > 2.0 {
+            f64::INFINITY
+        } else {
+            f64::NAN
+        }
+    }
+}
+
+impl Continuous for FisherF {
+    fn pdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            return 0.0;
+        }
+        let (d1, d2) = (self.df1, self.df2);
+        (0.5 * (d1 * d1.ln() + d2 * d2.ln()) + xlnx(0.5 * d1 - 1.0, x)
+            - 0.5 * (d1 + d2) * (d1 * x + d2).ln()
+            - ln_beta(0.5 * d1, 0.5 * d2))
+        .exp()
+    }
+}
+
+/// The exponential distribution with the given rate: the
+/// distribution of waiting times between events that occur
+/// independently at that average rate.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let e = Exponential::new(2.0).unwrap();
+/// assert!((e.cdf(1.0) - (1.0 - (-2f64).exp())).abs() < 1e-15);
+/// assert!((e.inverse_cdf(0.5) - 0.5 * 2f64.ln()).abs() < 1e-15);
+/// assert!(e.cdf(f64::NAN).is_nan() && e.sf(f64::NAN).is_nan());
+/// assert_eq!((0.5, 0.25), (e.mean(), e.variance()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exponential {
+    rate: f64,
+}
+
+impl Exponential {
+    /// Make an exponential distribution with positive rate
+    /// `rate`.
+    pub fn new(rate: f64) -> StatResult<Self> {
+        check_positive(rate, "rate must be positive")?;
+        Ok(Exponential { rate })
+    }
+}
+
+impl Distribution for Exponential {
+    fn cdf(&self, x: f64) -> f64 {
+        if x <= 0.0 {
+            return 0.0;
+        }
+        -(-self.rate * x).exp_m1()
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        if x <= 0.0 {
+            return 1.0;
+        }
+        (-self.rate * x).exp()
+    }
+
+    fn inverse_cdf(&self, p: f64) -> f64 {
+        if !is_probability(p) {
+            return f64::NAN;
+        }
+        -(-p).ln_1p() / self.rate
+    }
+
+    fn mean(&self) -> f64 {
+        self.rate.recip()
+    }
+
+    fn variance(&self) -> f64 {
+        (self.rate * self.rate).recip()
+    }
+}
+
+impl Continuous for Exponential {
+    fn pdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            0.0
+        } else {
+            self.rate * (-self.rate * x).exp()
+        }
+    }
+}
+
+/// The beta distribution on the unit interval with the
+/// given shape parameters.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let b = Beta::new(2.0, 3.0).unwrap();
+/// assert!((b.cdf(0.4) - 0.5248).abs() < 1e-14);
+/// assert!((b.pdf(0.5) - 1.5).abs() < 1e-14);
+/// assert!((b.cdf(b.inverse_cdf(0.9)) - 0.9).abs() < 1e-15);
+/// assert_eq!((0.4, 0.04), (b.mean(), b.variance()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Beta {
+    alpha: f64,
+    beta: f64,
+}
+
+impl Beta {
+    /// Make a beta distribution with positive shape
+    /// parameters `alpha` and `beta`.
+    pub fn new(alpha: f64, beta: f64) -> StatResult<Self> {
+        check_positive(alpha, "shape must be positive")?;
+        check_positive(beta, "shape must be positive")?;
+        Ok(Beta { alpha, beta })
+    }
+}
+
+impl Distribution for Beta {
+    fn cdf(&self, x: f64) -> f64 {
+        regularized_beta(self.alpha, self.beta, x.clamp(0.0, 1.0))
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        regularized_beta(self.beta, self.alpha, 1.0 - x.clamp(0.0, 1.0))
+    }
+
+    fn inverse_cdf(&self, p: f64) -> f64 {
+        invert(self, p, 0.0, 1.0)
+    }
+
+    fn mean(&self) -> f64 {
+        self.alpha / (self.alpha + self.beta)
+    }
+
+    fn variance(&self) -> f64 {
+        let total = self.alpha + self.beta;
+        self.alpha * self.beta / (total * total * (total + 1.0))
+    }
+}
+
+impl Continuous for Beta {
+    fn pdf(&self, x: f64) -> f64 {
+        if !(0.0..=1.0).contains(&x) {
+            return 0.0;
+        }
+        (xlnx(self.alpha - 1.0, x) + xln1p(self.beta - 1.0, -x) - ln_beta(self.alpha, self.beta))
+            .exp()
+    }
+}
+
+/// The continuous uniform distribution on an interval.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let u = Uniform::new(0.0, 4.0).unwrap();
+/// assert_eq!((0.25, 0.25), (u.cdf(1.0), u.pdf(1.0)));
+/// assert_eq!(2.0, u.inverse_cdf(0.5));
+/// assert_eq!((2.0, 16.0 / 12.0), (u.mean(), u.variance()));
+/// assert!(Uniform::new(1.0, 1.0).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Uniform {
+    lo: f64,
+    hi: f64,
+}
+
+impl Uniform {
+    /// Make a uniform distribution on `lo..hi`, which must
+    /// be finite with `lo` less than `hi`.
+    pub fn new(lo: f64, hi: f64) -> StatResult<Self> {
+        if lo.is_finite() && hi.is_finite() && lo < hi {
+            Ok(Uniform { lo, hi })
+        } else {
+            Err(StatError::InvalidParameter(
+                "bounds must be finite and increasing",
+            ))
+        }
+    }
+}
+
+impl Distribution for Uniform {
+    fn cdf(&self, x: f64) -> f64 {
+        ((x - self.lo) / (self.hi - self.lo)).clamp(0.0, 1.0)
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        ((self.hi - x) / (self.hi - self.lo)).clamp(0.0, 1.0)
+    }
+
+    fn inverse_cdf(&self, p: f64) -> f64 {
+        if !is_probability(p) {
+            return f64::NAN;
+        }
+        self.lo + p * (self.hi - self.lo)
+    }
+
+    fn mean(&self) -> f64 {
+        self.lo + 0.5 * (self.hi - self.lo)
+    }
+
+    fn variance(&self) -> f64 {
+        (self.hi - self.lo).powi(2) / 12.0
+    }
+}
+
+impl Continuous for Uniform {
+    fn pdf(&self, x: f64) -> f64 {
+        if (self.lo..=self.hi).contains(&x) {
+            (self.hi - self.lo).recip()
+        } else {
+            0.0
+        }
+    }
+}
+
+/// The binomial distribution: the distribution of the
+/// number of successes in a fixed number of independent
+/// trials with the same probability of success.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let b = Binomial::new(10, 0.5).unwrap();
+/// assert!((b.pmf(5) - 252.0 / 1024.0).abs() < 1e-15);
+/// assert!((b.cdf(5.0) - 638.0 / 1024.0).abs() < 1e-15);
+/// assert!((b.sf(5.0) - 386.0 / 1024.0).abs() < 1e-15);
+/// assert_eq!(5.0, b.inverse_cdf(0.5));
+/// assert_eq!((5.0, 2.5), (b.mean(), b.variance()));
+/// ```
+/// ```
+/// # use stats::*;
+/// let b = Binomial::new(3, 0.0).unwrap();
+/// assert_eq!((1.0, 0.0), (b.pmf(0), b.pmf(1)));
+/// assert_eq!(1.0, b.cdf(0.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Binomial {
+    trials: u64,
+    p: f64,
+}
+
+impl Binomial {
+    /// Make a binomial distribution for `trials` trials
+    /// each succeeding with probability `p`.
+    pub fn new(trials: u64, p: f64) -> StatResult<Self> {
+        if is_probability(p) {
+            Ok(Binomial { trials, p })
+        } else {
+            Err(StatError::InvalidParameter(
+                "probability must be between 0 and 1",
+            ))
+        }
+    }
+}
+
+impl Distribution for Binomial {
+    fn cdf(&self, x: f64) -> f64 {
+        let k = x.floor();
+        let n = self.trials as f64;
+        if k < 0.0 {
+            0.0
+        } else if k >= n {
+            1.0
+        } else {
+            regularized_beta(n - k, k + 1.0, 1.0 - self.p)
+        }
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        let k = x.floor();
+        let n = self.trials as f64;
+        if k < 0.0 {
+            1.0
+        } else if k >= n {
+            0.0
+        } else {
+            regularized_beta(k + 1.0, n - k, self.p)
+        }
+    }
+
+    fn inverse_cdf(&self, p: f64) -> f64 {
+        invert_discrete(self, p, self.trials as f64)
+    }
+
+    fn mean(&self) -> f64 {
+        self.trials as f64 * self.p
+    }
+
+    fn variance(&self) -> f64 {
+        self.trials as f64 * self.p * (1.0 - self.p)
+    }
+}
+
+impl Discrete for Binomial {
+    fn pmf(&self, k: u64) -> f64 {
+        if k > self.trials {
+            return 0.0;
+        }
+        let (n, k) = (self.trials as f64, k as f64);
+        let ln_choose = ln_gamma(n + 1.0) - ln_gamma(k + 1.0) - ln_gamma(n - k + 1.0);
+        (ln_choose + xlnx(k, self.p) + xln1p(n - k, -self.p)).exp()
+    }
+}
+
+/// The Poisson distribution with the given mean: the
+/// distribution of the number of events in an interval
+/// when they occur independently at a constant average
+/// rate.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let p = Poisson::new(2.0).unwrap();
+/// assert!((p.pmf(0) - (-2f64).exp()).abs() < 1e-15);
+/// assert!((p.cdf(1.0) - 3.0 * (-2f64).exp()).abs() < 1e-15);
+/// assert_eq!(2.0, p.inverse_cdf(0.5));
+/// assert_eq!((2.0, 2.0), (p.mean(), p.variance()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Poisson {
+    lambda: f64,
+}
+
+impl Poisson {
+    /// Make a Poisson distribution with positive mean
+    /// `lambda`.
+    pub fn new(lambda: f64) -> StatResult<Self> {
+        check_positive(lambda, "mean must be positive")?;
+        Ok(Poisson { lambda })
+    }
+}
+
+impl Distribution for Poisson {
+    fn cdf(&self, x: f64) -> f64 {
+        let k = x.floor();
+        if k < 0.0 {
+            0.0
+        } else {
+            regularized_gamma_q(k + 1.0, self.lambda)
+        }
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        let k = x.floor();
+        if k < 0.0 {
+            1.0
+        } else {
+            regularized_gamma_p(k + 1.0, self.lambda)
+        }
+    }
+
+    fn inverse_cdf(&self, p: f64) -> f64 {
+        invert_discrete(self, p, f64::INFINITY)
+    }
+
+    fn mean(&self) -> f64 {
+        self.lambda
+    }
+
+    fn variance(&self) -> f64 {
+        self.lambda
+    }
+}
+
+impl Discrete for Poisson {
+    fn pmf(&self, k: u64) -> f64 {
+        let k = k as f64;
+        (xlnx(k, self.lambda) - self.lambda - ln_gamma(k + 1.0)).exp()
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Empirical cumulative distribution functions.
+
+use crate::distributions::*;
+use crate::error::*;
+use crate::moments::*;
+use crate::nan::*;
+use crate::num::*;
+use crate::order::*;
+
+/// The empirical distribution of a sample: each of its
+/// values has equal probability. Its CDF at `x` is the
+/// fraction of the values at most `x`. Under
+/// [`NanPolicy::Propagate`] the distribution of a sample
+/// containing a NaN is NaN everywhere.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let ecdf = Ecdf::new(&[3.0, 1.0, 2.0, 2.0], NanPolicy::Error).unwrap();
+/// assert_eq!(&[1.0, 2.0, 2.0, 3.0], ecdf.values());
+/// assert_eq!((0.0, 0.25, 0.75, 1.0), (ecdf.cdf(0.5), ecdf.cdf(1.0), ecdf.cdf(2.5), ecdf.cdf(3.0)));
+/// assert_eq!(0.25, ecdf.sf(2.0));
+/// assert_eq!((1.0, 2.0, 3.0), (ecdf.inverse_cdf(0.25), ecdf.inverse_cdf(0.3), ecdf.inverse_cdf(1.0)));
+/// assert_eq!((2.0, 0.5), (ecdf.mean(), ecdf.variance()));
+/// ```
+/// ```
+/// # use stats::*;
+/// let ecdf = Ecdf::new(&[1.0, f64::NAN], NanPolicy::Propagate).unwrap();
+/// assert!(ecdf.cdf(2.0).is_nan());
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Err(StatError::Empty), Ecdf::new::<f64>(&[], NanPolicy::Error));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ecdf {
+    values: Vec<f64>,
+}
+
+impl Ecdf {
+    /// Make the empirical distribution of the input
+    /// values, of which there must be at least one.
+    pub fn new<T: Numeric>(nums: &[T], nan: NanPolicy) -> StatResult<Self> {
+        let propagated = Ecdf {
+            values: vec![f64::NAN],
+        };
+        nan.stat_or(nums, propagated, |nums| {
+            if nums.is_empty() {
+                return Err(StatError::Empty);
+            }
+            let mut values = nums.to_owned();
+            values.sort_unstable_by(cmp_f64);
+            Ok(Ecdf { values })
+        })
+    }
+
+    /// The sample values, in increasing order.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// True if a NaN was propagated from the sample.
+    fn is_nan(&self) -> bool {
+        self.values[0].is_nan()
+    }
+
+    /// Number of sample values at most `x`.
+    fn count(&self, x: f64) -> usize {
+        self.values.partition_point(|&v| v <= x)
+    }
+}
+
+impl Distribution for Ecdf {
+    fn cdf(&self, x: f64) -> f64 {
+        if self.is_nan() || x.is_nan() {
+            return f64::NAN;
+        }
+        self.count(x) as f64 / self.values.len() as f64
+    }
+
+    fn sf(&self, x: f64) -> f64 {
+        if self.is_nan() || x.is_nan() {
+            return f64::NAN;
+        }
+        let n = self.values.len();
+        (n - self.count(x)) as f64 / n as f64
+    }
+
+    fn inverse_cdf(&self, p: f64) -> f64 {
+        if !(0.0..=1.0).contains(&p) {
+            return f64::NAN;
+        }
+        let n = self.values.len();
+        let k = ((p * n as f64).ceil() as usize).clamp(1, n);
+        self.values[k - 1]
+    }
+
+    fn mean(&self) -> f64 {
+        let moments: Moments = self.values.iter().collect();
+        moments.mean().unwrap_or(f64::NAN)
+    }
+
+    fn variance(&self) -> f64 {
+        let moments: Moments = self.values.iter().collect();
+        moments.variance(0).unwrap_or(f64::NAN)
+    }
+}
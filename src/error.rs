@@ -0,0 +1,66 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Reasons a statistic may be undefined.
+
+use std::fmt;
+
+/// Reason a statistic could not be computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatError {
+    /// The input was empty.
+    Empty,
+    /// The input contained a NaN.
+    NanInput,
+    /// The result was infinite or NaN, for example because
+    /// the input contained infinities or the computation
+    /// overflowed.
+    NonFinite,
+    /// The statistic needs at least `needed` values, but
+    /// only `got` were supplied.
+    TooFewSamples { needed: usize, got: usize },
+    /// Two inputs that should pair up value for value had
+    /// different lengths.
+    LengthMismatch { left: usize, right: usize },
+    /// The input values were all equal, so a statistic that
+    /// is scaled by their spread is undefined.
+    ZeroVariance,
+    /// A parameter of the statistic was out of range. The
+    /// string describes the valid range.
+    InvalidParameter(&'static str),
+}
+
+impl fmt::Display for StatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StatError::Empty => write!(f, "empty input"),
+            StatError::NanInput => write!(f, "input contains NaN"),
+            StatError::NonFinite => write!(f, "result is not finite"),
+            StatError::TooFewSamples { needed, got } => {
+                write!(f, "need at least {} values, got {}", needed, got)
+            }
+            StatError::LengthMismatch { left, right } => {
+                write!(f, "inputs have different lengths {} and {}", left, right)
+            }
+            StatError::ZeroVariance => write!(f, "input values are all equal"),
+            StatError::InvalidParameter(what) => write!(f, "invalid parameter: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for StatError {}
+
+/// Result of a statistic: the value, or the reason it is
+/// undefined.
+pub type StatResult<T = f64> = Result<T, StatError>;
+
+/// Check that a computed statistic is finite.
+pub(crate) fn finite(x: f64) -> StatResult {
+    if x.is_finite() {
+        Ok(x)
+    } else {
+        Err(StatError::NonFinite)
+    }
+}
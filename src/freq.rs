@@ -0,0 +1,175 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Frequency tables and modes.
+
+use crate::error::*;
+use crate::nan::*;
+use crate::num::*;
+use crate::order::*;
+
+/// Check that a binning tolerance is finite and
+/// nonnegative.
+fn check_tolerance(tolerance: f64) -> StatResult<()> {
+    if tolerance.is_finite() && tolerance >= 0.0 {
+        Ok(())
+    } else {
+        Err(StatError::InvalidParameter(
+            "tolerance must be finite and nonnegative",
+        ))
+    }
+}
+
+/// The distinct values of the NaN-free `nums` in increasing
+/// order with the number of times each occurs, after
+/// rounding each to the nearest multiple of `tolerance` if
+/// it is positive.
+fn count_values(nums: &[f64], tolerance: f64) -> Vec<(f64, usize)> {
+    let mut nums: Vec<f64> = if tolerance > 0.0 {
+        nums.iter()
+            .map(|x| (x / tolerance).round() * tolerance)
+            .collect()
+    } else {
+        nums.to_owned()
+    };
+    nums.sort_unstable_by(cmp_f64);
+    let mut table: Vec<(f64, usize)> = Vec::new();
+    for x in nums {
+        match table.last_mut() {
+            Some((y, count)) if *y == x => *count += 1,
+            _ => table.push((x, 1)),
+        }
+    }
+    table
+}
+
+/// Frequency table of input values: the distinct values in
+/// increasing order, each with the number of times it
+/// occurs. If `tolerance` is positive, floating-point
+/// values are first rounded to the nearest multiple of it,
+/// so that values within the same bin are counted
+/// together. Under [`NanPolicy::Propagate`] any NaNs are
+/// counted together in a last entry.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [3.0, 1.0, 3.0, 2.0, 3.0, 1.0];
+/// let table = frequencies(&nums, 0.0, NanPolicy::Error);
+/// assert_eq!(Ok(vec![(1.0, 2), (2.0, 1), (3.0, 3)]), table);
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [0.98, 1.01, 1.49, 2.02];
+/// let table = frequencies(&nums, 0.5, NanPolicy::Error);
+/// assert_eq!(Ok(vec![(1.0, 2), (1.5, 1), (2.0, 1)]), table);
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, std::f64::NAN, 1.0, std::f64::NAN];
+/// let table = frequencies(&nums, 0.0, NanPolicy::Propagate).unwrap();
+/// assert_eq!((1.0, 2), table[0]);
+/// assert!(table[1].0.is_nan() && table[1].1 == 2);
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(vec![]), frequencies::<f64>(&[], 0.0, NanPolicy::Error));
+/// ```
+pub fn frequencies<T: Numeric>(
+    nums: &[T],
+    tolerance: f64,
+    nan: NanPolicy,
+) -> StatResult<Vec<(f64, usize)>> {
+    check_tolerance(tolerance)?;
+    match nan.apply(nums)? {
+        Some(nums) => Ok(count_values(&nums, tolerance)),
+        None => {
+            let values: Vec<f64> = nums
+                .iter()
+                .map(|x| x.to_f64())
+                .filter(|x| !x.is_nan())
+                .collect();
+            let mut table = count_values(&values, tolerance);
+            table.push((f64::NAN, nums.len() - values.len()));
+            Ok(table)
+        }
+    }
+}
+
+/// Modes of input values: the values that occur most
+/// often, in increasing order. There is more than one mode
+/// if several values are tied. Values are binned with
+/// `tolerance` as for [`frequencies`]. The mode of an empty
+/// list is undefined.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [3.0, 1.0, 3.0, 2.0, 3.0, 1.0];
+/// assert_eq!(Ok(vec![3.0]), mode(&nums, 0.0, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [2.0, 1.0, 2.0, 1.0, 0.0];
+/// assert_eq!(Ok(vec![1.0, 2.0]), mode(&nums, 0.0, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [0.9, 1.1, 1.2, 2.0];
+/// assert_eq!(Ok(vec![1.0]), mode(&nums, 0.5, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Err(StatError::Empty), mode::<f64>(&[], 0.0, NanPolicy::Error));
+/// ```
+pub fn mode<T: Numeric>(nums: &[T], tolerance: f64, nan: NanPolicy) -> StatResult<Vec<f64>> {
+    let modes = mode_counts(nums, tolerance, nan)?;
+    Ok(modes.into_iter().map(|(x, _)| x).collect())
+}
+
+/// Modes of input values as for [`mode`], each with the
+/// number of times it occurs. A propagated NaN is given
+/// with the number of NaNs.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [2.0, 1.0, 2.0, 1.0, 0.0];
+/// assert_eq!(Ok(vec![(1.0, 2), (2.0, 2)]), mode_counts(&nums, 0.0, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, f64::NAN, 1.0, f64::NAN, f64::NAN];
+/// let modes = mode_counts(&nums, 0.0, NanPolicy::Propagate).unwrap();
+/// assert!(modes.len() == 1 && modes[0].0.is_nan() && modes[0].1 == 3);
+/// ```
+pub fn mode_counts<T: Numeric>(
+    nums: &[T],
+    tolerance: f64,
+    nan: NanPolicy,
+) -> StatResult<Vec<(f64, usize)>> {
+    check_tolerance(tolerance)?;
+    match nan.apply(nums)? {
+        Some(nums) => {
+            let table = count_values(&nums, tolerance);
+            let most = table
+                .iter()
+                .map(|&(_, count)| count)
+                .max()
+                .ok_or(StatError::Empty)?;
+            Ok(table
+                .into_iter()
+                .filter(|&(_, count)| count == most)
+                .collect())
+        }
+        None => {
+            let nans = nums.iter().filter(|x| x.is_nan()).count();
+            Ok(vec![(f64::NAN, nans)])
+        }
+    }
+}
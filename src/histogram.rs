@@ -0,0 +1,345 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Histograms, with bins given explicitly or chosen from
+//! the data by the usual bin-width rules.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::*;
+use crate::moments::*;
+use crate::nan::*;
+use crate::num::*;
+use crate::quantile::*;
+
+/// How to choose equal-width bins covering the range of
+/// the data. The rules are those of NumPy's
+/// `histogram_bin_edges`, except that the rules choosing a
+/// bin width give at most one bin per value, so that an
+/// outlier far from narrowly spread data cannot call for an
+/// enormous number of bins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BinRule {
+    /// Exactly this many bins.
+    Count(usize),
+    /// Sturges' rule: `log2(n) + 1` bins. Suits small,
+    /// roughly normal samples. This is the default.
+    #[default]
+    Sturges,
+    /// Scott's rule: bins of width `3.5 s / n^(1/3)`,
+    /// where `s` is the standard deviation. Optimal for
+    /// normal data.
+    Scott,
+    /// The Freedman–Diaconis rule: bins of width
+    /// `2 IQR / n^(1/3)`, where `IQR` is the interquartile
+    /// range. Robust to outliers.
+    FreedmanDiaconis,
+    /// `sqrt(n)` bins, as used by spreadsheets.
+    Sqrt,
+}
+
+impl fmt::Display for BinRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BinRule::Count(n) => write!(f, "{}", n),
+            BinRule::Sturges => write!(f, "sturges"),
+            BinRule::Scott => write!(f, "scott"),
+            BinRule::FreedmanDiaconis => write!(f, "fd"),
+            BinRule::Sqrt => write!(f, "sqrt"),
+        }
+    }
+}
+
+/// Parse a bin rule from its name: `sturges`, `scott`,
+/// `fd` (or `freedman-diaconis`) or `sqrt`, or from a
+/// positive number of bins.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(BinRule::FreedmanDiaconis), "fd".parse());
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(BinRule::Count(12)), "12".parse());
+/// ```
+/// ```
+/// # use stats::*;
+/// assert!("0".parse::<BinRule>().is_err());
+/// ```
+impl FromStr for BinRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sturges" => Ok(BinRule::Sturges),
+            "scott" => Ok(BinRule::Scott),
+            "fd" | "freedman-diaconis" => Ok(BinRule::FreedmanDiaconis),
+            "sqrt" => Ok(BinRule::Sqrt),
+            _ => match s.parse() {
+                Ok(n) if n > 0 => Ok(BinRule::Count(n)),
+                _ => Err(format!("unknown bin rule {}", s)),
+            },
+        }
+    }
+}
+
+impl BinRule {
+    /// Number of bins for the NaN-free, nonempty `nums`,
+    /// which span `range`.
+    fn bins(self, nums: &[f64], range: f64) -> StatResult<usize> {
+        let n = nums.len() as f64;
+        let width = match self {
+            BinRule::Count(bins) => return Ok(bins),
+            BinRule::Sturges => return Ok(n.log2().ceil() as usize + 1),
+            BinRule::Sqrt => return Ok(n.sqrt().ceil() as usize),
+            BinRule::Scott => {
+                let sd = nums.iter().collect::<Moments>().stddev(0)?;
+                (24.0 * std::f64::consts::PI.sqrt() / n).cbrt() * sd
+            }
+            BinRule::FreedmanDiaconis => {
+                let ps = [0.25, 0.75];
+                let qs = quantiles(nums, &ps, QuantileMethod::Linear, NanPolicy::Error)?;
+                2.0 * (qs[1] - qs[0]) / n.cbrt()
+            }
+        };
+        if width > 0.0 {
+            Ok(((range / width).ceil() as usize).clamp(1, nums.len()))
+        } else {
+            Ok(1)
+        }
+    }
+}
+
+/// Counts of values falling into bins. Bins are half-open
+/// intervals `[lo, hi)`, except that the last bin also
+/// includes its upper edge. Values below the first edge or
+/// above the last are counted as underflow or overflow,
+/// and NaNs are counted separately.
+///
+/// A histogram built with [`with_range`](Histogram::with_range)
+/// or [`with_edges`](Histogram::with_edges) can be filled
+/// from a stream of values in constant memory.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let mut h = Histogram::with_range(0.0, 10.0, 5).unwrap();
+/// h.extend(&[-1.0, 0.0, 1.0, 2.5, 9.0, 10.0, 11.0]);
+/// assert_eq!(&[0.0, 2.0, 4.0, 6.0, 8.0, 10.0], h.edges());
+/// assert_eq!(&[2, 1, 0, 0, 2], h.counts());
+/// assert_eq!((1, 1), (h.underflow(), h.overflow()));
+/// ```
+/// ```
+/// # use stats::*;
+/// let mut h = Histogram::with_edges(vec![0.0, 1.0, 3.0]).unwrap();
+/// h.extend(&[0.5, 1.5, 2.5, 2.5]);
+/// assert_eq!(Ok(vec![0.25, 0.375]), h.densities());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    edges: Vec<f64>,
+    counts: Vec<u64>,
+    underflow: u64,
+    overflow: u64,
+    nans: u64,
+}
+
+impl Histogram {
+    /// Make an empty histogram with `bins` bins of equal
+    /// width from `lo` to `hi`.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use stats::*;
+    /// assert!(Histogram::with_range(1.0, 1.0, 3).is_err());
+    /// ```
+    pub fn with_range(lo: f64, hi: f64, bins: usize) -> StatResult<Self> {
+        if !(lo.is_finite() && hi.is_finite() && lo < hi) {
+            return Err(StatError::InvalidParameter(
+                "histogram range must be finite and nonempty",
+            ));
+        }
+        if bins == 0 {
+            return Err(StatError::InvalidParameter(
+                "histogram must have at least one bin",
+            ));
+        }
+        let width = (hi - lo) / bins as f64;
+        let mut edges: Vec<f64> = (0..bins).map(|i| lo + i as f64 * width).collect();
+        edges.push(hi);
+        Self::with_edges(edges)
+    }
+
+    /// Make an empty histogram with the given bin edges,
+    /// which must be finite and strictly increasing. There
+    /// is one fewer bin than edges.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use stats::*;
+    /// assert!(Histogram::with_edges(vec![0.0, 2.0, 1.0]).is_err());
+    /// ```
+    pub fn with_edges(edges: Vec<f64>) -> StatResult<Self> {
+        if edges.len() < 2 {
+            return Err(StatError::InvalidParameter(
+                "histogram must have at least one bin",
+            ));
+        }
+        if edges.iter().any(|e| !e.is_finite()) || edges.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(StatError::InvalidParameter(
+                "histogram edges must be finite and strictly increasing",
+            ));
+        }
+        Ok(Histogram {
+            counts: vec![0; edges.len() - 1],
+            edges,
+            underflow: 0,
+            overflow: 0,
+            nans: 0,
+        })
+    }
+
+    /// Make a histogram of input values with equal-width
+    /// bins spanning them, chosen by `rule`. If all the
+    /// values are equal, there is a single bin of width one
+    /// centered on them. Unless the NaN policy is
+    /// [`NanPolicy::Error`], NaNs are counted separately.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use stats::*;
+    /// let nums: Vec<f64> = (0..16).map(f64::from).collect();
+    /// let h = Histogram::from_rule(&nums, BinRule::Sqrt, NanPolicy::Error).unwrap();
+    /// assert_eq!(&[4, 4, 4, 4], h.counts());
+    /// ```
+    /// ```
+    /// # use stats::*;
+    /// let nums: Vec<f64> = (0..16).map(f64::from).collect();
+    /// let h = Histogram::from_rule(&nums, BinRule::Sturges, NanPolicy::Error).unwrap();
+    /// assert_eq!(5, h.counts().len());
+    /// assert_eq!(16, h.total());
+    /// ```
+    /// ```
+    /// # use stats::*;
+    /// let nums = [2.0, 2.0, std::f64::NAN];
+    /// let h = Histogram::from_rule(&nums, BinRule::Scott, NanPolicy::Skip).unwrap();
+    /// assert_eq!((&[1.5, 2.5][..], &[2][..]), (h.edges(), h.counts()));
+    /// assert_eq!(1, h.nans());
+    /// ```
+    /// ```
+    /// # use stats::*;
+    /// let h = Histogram::from_rule::<f64>(&[], BinRule::FreedmanDiaconis, NanPolicy::Error);
+    /// assert_eq!(Err(StatError::Empty), h);
+    /// ```
+    /// ```
+    /// # use stats::*;
+    /// let nums = [0.0, 0.0, 0.0, 1.0, 1.0, 1e15];
+    /// let h = Histogram::from_rule(&nums, BinRule::FreedmanDiaconis, NanPolicy::Error).unwrap();
+    /// assert_eq!(&[5, 0, 0, 0, 0, 1], h.counts());
+    /// ```
+    pub fn from_rule<T: Numeric>(nums: &[T], rule: BinRule, nan: NanPolicy) -> StatResult<Self> {
+        if nan == NanPolicy::Error && nums.iter().any(|x| x.is_nan()) {
+            return Err(StatError::NanInput);
+        }
+        let values: Vec<f64> = nums
+            .iter()
+            .map(|x| x.to_f64())
+            .filter(|x| !x.is_nan())
+            .collect();
+        let m: Moments = values.iter().collect();
+        let (lo, hi) = (m.min()?, m.max()?);
+        let mut h = if lo < hi {
+            Self::with_range(lo, hi, rule.bins(&values, hi - lo)?)?
+        } else {
+            Self::with_range(lo - 0.5, hi + 0.5, 1)?
+        };
+        h.extend(nums.iter().map(|x| x.to_f64()));
+        Ok(h)
+    }
+
+    /// Count a value.
+    pub fn push(&mut self, x: f64) {
+        let last = self.edges.len() - 1;
+        if x.is_nan() {
+            self.nans += 1;
+        } else if x < self.edges[0] {
+            self.underflow += 1;
+        } else if x > self.edges[last] {
+            self.overflow += 1;
+        } else {
+            let bin = self.edges.partition_point(|&e| e <= x);
+            self.counts[bin.min(last) - 1] += 1;
+        }
+    }
+
+    /// The bin edges, one more than the number of bins.
+    pub fn edges(&self) -> &[f64] {
+        &self.edges
+    }
+
+    /// Number of values counted in each bin.
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Number of values below the first edge.
+    pub fn underflow(&self) -> u64 {
+        self.underflow
+    }
+
+    /// Number of values above the last edge.
+    pub fn overflow(&self) -> u64 {
+        self.overflow
+    }
+
+    /// Number of NaNs pushed.
+    pub fn nans(&self) -> u64 {
+        self.nans
+    }
+
+    /// Number of values counted in the bins.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Estimated probability density in each bin: its count
+    /// divided by the total count in the bins and by its
+    /// width, so that the densities integrate to one. The
+    /// densities are undefined if no values fell in the
+    /// bins.
+    pub fn densities(&self) -> StatResult<Vec<f64>> {
+        let total = self.total();
+        if total == 0 {
+            return Err(StatError::Empty);
+        }
+        Ok(self
+            .counts
+            .iter()
+            .zip(self.edges.windows(2))
+            .map(|(&count, w)| count as f64 / (total as f64 * (w[1] - w[0])))
+            .collect())
+    }
+}
+
+impl Extend<f64> for Histogram {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl<'a> Extend<&'a f64> for Histogram {
+    fn extend<I: IntoIterator<Item = &'a f64>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
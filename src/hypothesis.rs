@@ -0,0 +1,900 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Hypothesis tests on samples.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::distributions::*;
+use crate::ecdf::*;
+use crate::error::*;
+use crate::moments::*;
+use crate::nan::*;
+use crate::num::*;
+use crate::order::*;
+use crate::rank::*;
+use crate::special::*;
+
+/// The alternative hypothesis of a test: the departure from
+/// the null hypothesis whose probability is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Alternative {
+    /// The statistic differs from its null value in either
+    /// direction. This is the default.
+    #[default]
+    TwoSided,
+    /// The statistic is less than its null value.
+    Less,
+    /// The statistic is greater than its null value.
+    Greater,
+}
+
+impl fmt::Display for Alternative {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Alternative::TwoSided => write!(f, "two-sided"),
+            Alternative::Less => write!(f, "less"),
+            Alternative::Greater => write!(f, "greater"),
+        }
+    }
+}
+
+/// Parse an alternative hypothesis from its name:
+/// `two-sided`, `less` or `greater`.
+impl FromStr for Alternative {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "two-sided" => Ok(Alternative::TwoSided),
+            "less" => Ok(Alternative::Less),
+            "greater" => Ok(Alternative::Greater),
+            _ => Err(format!("unknown alternative {}", s)),
+        }
+    }
+}
+
+/// The p-value of `statistic` under the `alternative`,
+/// given its distribution `dist` under the null hypothesis,
+/// which is symmetric about 0 for a two-sided test.
+pub(crate) fn p_value<D: Distribution>(dist: &D, statistic: f64, alternative: Alternative) -> f64 {
+    match alternative {
+        Alternative::TwoSided => (2.0 * dist.sf(statistic.abs())).min(1.0),
+        Alternative::Less => dist.cdf(statistic),
+        Alternative::Greater => dist.sf(statistic),
+    }
+}
+
+/// Result of a t-test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TTest {
+    /// The t statistic.
+    pub statistic: f64,
+    /// Degrees of freedom of the t distribution of the
+    /// statistic under the null hypothesis.
+    pub df: f64,
+    /// Probability under the null hypothesis of a statistic
+    /// at least as extreme as that observed, in the
+    /// direction of the alternative hypothesis.
+    pub p_value: f64,
+    /// Cohen's d: the difference in means in units of
+    /// standard deviation.
+    pub effect_size: f64,
+}
+
+impl TTest {
+    /// Test result of NaNs, for NaNs propagated from the
+    /// input.
+    fn nan() -> Self {
+        TTest {
+            statistic: f64::NAN,
+            df: f64::NAN,
+            p_value: f64::NAN,
+            effect_size: f64::NAN,
+        }
+    }
+
+    /// Test result for the t statistic `statistic` with `df`
+    /// degrees of freedom.
+    fn new(
+        statistic: f64,
+        df: f64,
+        alternative: Alternative,
+        effect_size: f64,
+    ) -> StatResult<Self> {
+        let t = StudentsT::new(df)?;
+        Ok(TTest {
+            statistic: finite(statistic)?,
+            df,
+            p_value: p_value(&t, statistic, alternative),
+            effect_size,
+        })
+    }
+}
+
+/// Mean, sample variance and count of a NaN-free sample of
+/// at least two values.
+fn sample_stats(nums: &[f64]) -> StatResult<(f64, f64, f64)> {
+    let moments: Moments = nums.iter().collect();
+    let variance = moments.variance(1)?;
+    Ok((moments.mean()?, variance, nums.len() as f64))
+}
+
+/// One-sample t-test of whether the input values are drawn
+/// from a population with mean `mu`. The statistic is
+/// positive when the sample mean exceeds `mu`. The values
+/// should be roughly normal or numerous, and at least two
+/// are needed. The effect size is the difference of the
+/// means divided by the sample standard deviation.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, 2.0, 3.0, 4.0, 5.0];
+/// let t = t_test_one_sample(&nums, 2.0, Alternative::TwoSided, NanPolicy::Error).unwrap();
+/// assert!((t.statistic - 2f64.sqrt()).abs() < 1e-15);
+/// assert_eq!(4.0, t.df);
+/// assert!((t.p_value - 0.23019964108049895).abs() < 1e-14);
+/// assert!((t.effect_size - 0.4f64.sqrt()).abs() < 1e-15);
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, 2.0, 3.0, 4.0, 5.0];
+/// let t = t_test_one_sample(&nums, 2.0, Alternative::Greater, NanPolicy::Error).unwrap();
+/// assert!((t.p_value - 0.23019964108049895 / 2.0).abs() < 1e-14);
+/// ```
+/// ```
+/// # use stats::*;
+/// let t = t_test_one_sample(&[2.0, 2.0], 1.0, Alternative::TwoSided, NanPolicy::Error);
+/// assert_eq!(Err(StatError::ZeroVariance), t);
+/// ```
+pub fn t_test_one_sample<T: Numeric>(
+    nums: &[T],
+    mu: f64,
+    alternative: Alternative,
+    nan: NanPolicy,
+) -> StatResult<TTest> {
+    nan.stat_or(nums, TTest::nan(), |nums| {
+        let (mean, variance, n) = sample_stats(nums)?;
+        if variance == 0.0 {
+            return Err(StatError::ZeroVariance);
+        }
+        let sd = variance.sqrt();
+        let statistic = (mean - mu) / (sd / n.sqrt());
+        TTest::new(statistic, n - 1.0, alternative, (mean - mu) / sd)
+    })
+}
+
+/// Paired t-test of whether the paired values `xs` and
+/// `ys`, as before and after measurements of the same
+/// subjects, have the same mean: a one-sample test of
+/// their differences `x - y` against 0. The inputs must
+/// have the same length. NaN handling applies to the
+/// pairs.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let xs = [1.0, 2.0, 3.0, 4.0, 5.0];
+/// let ys = [2.0, 2.0, 4.0, 5.0, 7.0];
+/// let t = t_test_paired(&xs, &ys, Alternative::TwoSided, NanPolicy::Error).unwrap();
+/// assert!((t.statistic + 10f64.sqrt()).abs() < 1e-14);
+/// assert!((t.p_value - 0.034109423167409725).abs() < 1e-14);
+/// ```
+pub fn t_test_paired<T: Numeric, U: Numeric>(
+    xs: &[T],
+    ys: &[U],
+    alternative: Alternative,
+    nan: NanPolicy,
+) -> StatResult<TTest> {
+    nan.stat2_or(xs, ys, TTest::nan(), |xs, ys| {
+        let diffs: Vec<f64> = xs.iter().zip(ys).map(|(x, y)| x - y).collect();
+        t_test_one_sample(&diffs, 0.0, alternative, NanPolicy::Error)
+    })
+}
+
+/// Two-sample t-test, with pooled variance, of whether the
+/// independent samples `xs` and `ys` come from populations
+/// with the same mean, assuming the populations have the
+/// same variance. The statistic is positive when the mean
+/// of `xs` is larger. Each sample needs at least two
+/// values; NaN handling applies to each separately. The
+/// effect size is the difference of the means divided by
+/// the pooled standard deviation.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let xs = [1.0, 2.0, 3.0, 4.0, 5.0];
+/// let ys = [3.0, 4.0, 5.0, 6.0, 7.0];
+/// let t = t_test_two_sample(&xs, &ys, Alternative::TwoSided, NanPolicy::Error).unwrap();
+/// assert_eq!((-2.0, 8.0), (t.statistic, t.df));
+/// assert!((t.p_value - 0.08051623795726257).abs() < 1e-14);
+/// assert!((t.effect_size + 1.6f64.sqrt()).abs() < 1e-15);
+/// ```
+pub fn t_test_two_sample<T: Numeric, U: Numeric>(
+    xs: &[T],
+    ys: &[U],
+    alternative: Alternative,
+    nan: NanPolicy,
+) -> StatResult<TTest> {
+    nan.stat_samples_or(xs, ys, TTest::nan(), |xs, ys| {
+        let (mean_x, var_x, n_x) = sample_stats(xs)?;
+        let (mean_y, var_y, n_y) = sample_stats(ys)?;
+        let df = n_x + n_y - 2.0;
+        let pooled = ((n_x - 1.0) * var_x + (n_y - 1.0) * var_y) / df;
+        if pooled == 0.0 {
+            return Err(StatError::ZeroVariance);
+        }
+        let diff = mean_x - mean_y;
+        let se = (pooled * (1.0 / n_x + 1.0 / n_y)).sqrt();
+        TTest::new(diff / se, df, alternative, diff / pooled.sqrt())
+    })
+}
+
+/// Welch's t-test of whether the independent samples `xs`
+/// and `ys` come from populations with the same mean,
+/// without assuming the populations have the same variance.
+/// The degrees of freedom are from the Welch–Satterthwaite
+/// approximation, and need not be an integer. Otherwise as
+/// for [`t_test_two_sample`], except that the effect size
+/// divides by the root mean of the two sample variances.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let xs = [1.0, 2.0, 3.0, 4.0, 5.0];
+/// let ys = [2.0, 4.0, 6.0, 8.0, 10.0, 12.0];
+/// let t = welch_t_test(&xs, &ys, Alternative::TwoSided, NanPolicy::Error).unwrap();
+/// assert!((t.statistic + 4.0 / (17.0f64 / 6.0).sqrt()).abs() < 1e-14);
+/// assert!((t.df - 289.0 / 41.45).abs() < 1e-13);
+/// ```
+/// ```
+/// # use stats::*;
+/// let xs = [1.0, 2.0, 3.0, 4.0, 5.0];
+/// let ys = [3.0, 4.0, 5.0, 6.0, 7.0];
+/// let pooled = t_test_two_sample(&xs, &ys, Alternative::Less, NanPolicy::Error).unwrap();
+/// let welch = welch_t_test(&xs, &ys, Alternative::Less, NanPolicy::Error).unwrap();
+/// assert_eq!(pooled, welch);
+/// ```
+pub fn welch_t_test<T: Numeric, U: Numeric>(
+    xs: &[T],
+    ys: &[U],
+    alternative: Alternative,
+    nan: NanPolicy,
+) -> StatResult<TTest> {
+    nan.stat_samples_or(xs, ys, TTest::nan(), |xs, ys| {
+        let (mean_x, var_x, n_x) = sample_stats(xs)?;
+        let (mean_y, var_y, n_y) = sample_stats(ys)?;
+        if var_x == 0.0 && var_y == 0.0 {
+            return Err(StatError::ZeroVariance);
+        }
+        let (se2_x, se2_y) = (var_x / n_x, var_y / n_y);
+        let se2 = se2_x + se2_y;
+        let df = se2 * se2 / (se2_x * se2_x / (n_x - 1.0) + se2_y * se2_y / (n_y - 1.0));
+        let diff = mean_x - mean_y;
+        let effect_size = diff / (0.5 * (var_x + var_y)).sqrt();
+        TTest::new(diff / se2.sqrt(), df, alternative, effect_size)
+    })
+}
+
+/// Result of a rank test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RankTest {
+    /// The rank statistic.
+    pub statistic: f64,
+    /// Probability under the null hypothesis of a statistic
+    /// at least as extreme as that observed, in the
+    /// direction of the alternative hypothesis.
+    pub p_value: f64,
+    /// True if the p-value is exact, rather than from the
+    /// normal approximation.
+    pub exact: bool,
+}
+
+impl RankTest {
+    /// Test result of NaNs, for NaNs propagated from the
+    /// input.
+    fn nan() -> Self {
+        RankTest {
+            statistic: f64::NAN,
+            p_value: f64::NAN,
+            exact: false,
+        }
+    }
+
+    /// Test result with the p-value from the exact
+    /// distribution `pmf` of the statistic, whose values
+    /// are the integers from 0.
+    fn exact(statistic: f64, pmf: &[f64], alternative: Alternative) -> Self {
+        let k = statistic as usize;
+        let below: f64 = pmf[..=k].iter().sum();
+        let above: f64 = pmf[k..].iter().sum();
+        let p_value = match alternative {
+            Alternative::TwoSided => (2.0 * below.min(above)).min(1.0),
+            Alternative::Less => below.min(1.0),
+            Alternative::Greater => above.min(1.0),
+        };
+        RankTest {
+            statistic,
+            p_value,
+            exact: true,
+        }
+    }
+
+    /// Test result with the p-value from the normal
+    /// approximation to the distribution of the statistic,
+    /// with the given mean and variance, corrected for
+    /// continuity.
+    fn normal(
+        statistic: f64,
+        mean: f64,
+        variance: f64,
+        alternative: Alternative,
+    ) -> StatResult<Self> {
+        if variance <= 0.0 {
+            return Err(StatError::ZeroVariance);
+        }
+        let correction = match alternative {
+            Alternative::TwoSided => 0.5 * (statistic - mean).signum(),
+            Alternative::Less => -0.5,
+            Alternative::Greater => 0.5,
+        };
+        // No correction when the statistic is at the mean.
+        let correction = if statistic == mean { 0.0 } else { correction };
+        let z = (statistic - mean - correction) / variance.sqrt();
+        Ok(RankTest {
+            statistic,
+            p_value: p_value(&Normal::standard(), z, alternative),
+            exact: false,
+        })
+    }
+}
+
+/// Samples smaller than this, without ties, get exact
+/// p-values from rank tests.
+const EXACT_LIMIT: usize = 50;
+
+/// Sum of `t^3 - t` over the sizes `t` of the groups of
+/// tied values in `nums`, for correcting the variance of a
+/// rank statistic for ties.
+fn tie_sum(nums: &[f64]) -> f64 {
+    let mut nums = nums.to_owned();
+    nums.sort_unstable_by(cmp_f64);
+    let mut sum = 0.0;
+    let mut start = 0;
+    while start < nums.len() {
+        let end = start
+            + nums[start..]
+                .iter()
+                .take_while(|&&x| x == nums[start])
+                .count();
+        let t = (end - start) as f64;
+        sum += t * t * t - t;
+        start = end;
+    }
+    sum
+}
+
+/// Exact distribution of the Mann–Whitney U statistic for
+/// samples of sizes `m` and `n` without ties, as the
+/// probabilities of 0 through `m * n`.
+fn mann_whitney_pmf(m: usize, n: usize) -> Vec<f64> {
+    // The largest of i + j values is from the first sample
+    // with probability i / (i + j), in which case it
+    // exceeds all j values of the second. pmfs[j] holds the
+    // distribution for samples of sizes i and j.
+    let mut pmfs: Vec<Vec<f64>> = vec![vec![1.0]; n + 1];
+    for i in 1..=m {
+        let mut prev = vec![1.0];
+        for (j, pmf) in pmfs.iter_mut().enumerate().skip(1) {
+            let (fi, fj) = (i as f64, j as f64);
+            let q = fi / (fi + fj);
+            let mut next = vec![0.0; i * j + 1];
+            for (u, p) in pmf.iter().enumerate() {
+                next[u + j] += q * p;
+            }
+            for (u, p) in prev.iter().enumerate() {
+                next[u] += (1.0 - q) * p;
+            }
+            *pmf = next;
+            prev = pmf.clone();
+        }
+    }
+    pmfs.pop().unwrap()
+}
+
+/// Exact distribution of the Wilcoxon signed-rank
+/// statistic for `n` nonzero differences without ties, as
+/// the probabilities of 0 through `n * (n + 1) / 2`.
+fn signed_rank_pmf(n: usize) -> Vec<f64> {
+    // Each rank k counts toward the statistic with
+    // probability one half.
+    let mut pmf = vec![1.0];
+    for k in 1..=n {
+        let mut next = vec![0.0; pmf.len() + k];
+        for (w, p) in pmf.iter().enumerate() {
+            next[w] += 0.5 * p;
+            next[w + k] += 0.5 * p;
+        }
+        pmf = next;
+    }
+    pmf
+}
+
+/// Mann–Whitney U test (the Wilcoxon rank-sum test) of
+/// whether the independent samples `xs` and `ys` come from
+/// the same distribution, against the alternative that
+/// values of one tend to be larger. Assumes nothing about
+/// the form of the distribution. The statistic U is the
+/// number of pairs of values, one from each sample, in
+/// which the value from `xs` is larger, counting ties as
+/// one half. Samples smaller than 50 without ties get an
+/// exact p-value; otherwise it is from the normal
+/// approximation, with corrections for ties and
+/// continuity. Each sample needs at least one value; NaN
+/// handling applies to each separately.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let xs = [1.1, 2.3, 3.5, 8.0];
+/// let ys = [4.2, 5.0, 6.1, 7.7, 9.9];
+/// let u = mann_whitney_u(&xs, &ys, Alternative::TwoSided, NanPolicy::Error).unwrap();
+/// assert_eq!((4.0, true), (u.statistic, u.exact));
+/// assert!((u.p_value - 4.0 / 21.0).abs() < 1e-15);
+/// let u = mann_whitney_u(&xs, &ys, Alternative::Less, NanPolicy::Error).unwrap();
+/// assert!((u.p_value - 2.0 / 21.0).abs() < 1e-15);
+/// ```
+/// ```
+/// # use stats::*;
+/// let xs = [1.0, 2.0, 2.0, 3.0, 4.0, 5.0];
+/// let ys = [3.0, 4.0, 5.0, 5.0, 6.0, 7.0, 8.0];
+/// let u = mann_whitney_u(&xs, &ys, Alternative::TwoSided, NanPolicy::Error).unwrap();
+/// assert_eq!((5.0, false), (u.statistic, u.exact));
+/// assert!((u.p_value - 0.025359042166350532).abs() < 1e-14);
+/// ```
+pub fn mann_whitney_u<T: Numeric, U: Numeric>(
+    xs: &[T],
+    ys: &[U],
+    alternative: Alternative,
+    nan: NanPolicy,
+) -> StatResult<RankTest> {
+    nan.stat_samples_or(xs, ys, RankTest::nan(), |xs, ys| {
+        if xs.is_empty() || ys.is_empty() {
+            return Err(StatError::Empty);
+        }
+        let nums = [xs, ys].concat();
+        let (m, n) = (xs.len(), ys.len());
+        let rank_sum: f64 = average_ranks(&nums)[..m].iter().sum();
+        let u = rank_sum - (m * (m + 1)) as f64 / 2.0;
+        let ties = tie_sum(&nums);
+        if ties == 0.0 && m < EXACT_LIMIT && n < EXACT_LIMIT {
+            return Ok(RankTest::exact(u, &mann_whitney_pmf(m, n), alternative));
+        }
+        let (fm, fn_, total) = (m as f64, n as f64, (m + n) as f64);
+        let variance = fm * fn_ / 12.0 * (total + 1.0 - ties / (total * (total - 1.0)));
+        RankTest::normal(u, 0.5 * fm * fn_, variance, alternative)
+    })
+}
+
+/// Wilcoxon signed-rank test of whether the differences
+/// `x - y` of the paired values `xs` and `ys` are symmetric
+/// about 0, against the alternative that they tend to one
+/// side. Zero differences are dropped. The statistic is
+/// the sum of the ranks of the absolute differences that
+/// are positive, with ties given average ranks. Fewer than
+/// 50 differences without ties get an exact p-value;
+/// otherwise it is from the normal approximation, with
+/// corrections for ties and continuity. The inputs must
+/// have the same length. NaN handling applies to the
+/// pairs.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let xs = [1.8, 2.9, 3.1, 4.4, 5.0, 6.3];
+/// let ys = [1.2, 3.3, 2.0, 3.5, 3.6, 4.1];
+/// let w = wilcoxon_signed_rank(&xs, &ys, Alternative::TwoSided, NanPolicy::Error).unwrap();
+/// assert_eq!((20.0, 0.0625, true), (w.statistic, w.p_value, w.exact));
+/// let w = wilcoxon_signed_rank(&xs, &ys, Alternative::Greater, NanPolicy::Error).unwrap();
+/// assert_eq!(0.03125, w.p_value);
+/// ```
+/// ```
+/// # use stats::*;
+/// let xs = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+/// let ys = [2.0, 1.0, 1.0, 2.0, 2.0, 6.0, 3.0];
+/// let w = wilcoxon_signed_rank(&xs, &ys, Alternative::TwoSided, NanPolicy::Error).unwrap();
+/// assert_eq!((19.5, false), (w.statistic, w.exact));
+/// assert!((w.p_value - 0.07313979965890893).abs() < 1e-14);
+/// ```
+/// ```
+/// # use stats::*;
+/// let w = wilcoxon_signed_rank(&[1.0, 2.0], &[1.0, 2.0], Alternative::TwoSided, NanPolicy::Error);
+/// assert_eq!(Err(StatError::ZeroVariance), w);
+/// ```
+pub fn wilcoxon_signed_rank<T: Numeric, U: Numeric>(
+    xs: &[T],
+    ys: &[U],
+    alternative: Alternative,
+    nan: NanPolicy,
+) -> StatResult<RankTest> {
+    nan.stat2_or(xs, ys, RankTest::nan(), |xs, ys| {
+        if xs.is_empty() {
+            return Err(StatError::Empty);
+        }
+        let diffs: Vec<f64> = xs
+            .iter()
+            .zip(ys)
+            .map(|(x, y)| x - y)
+            .filter(|&d| d != 0.0)
+            .collect();
+        let sizes: Vec<f64> = diffs.iter().map(|d| d.abs()).collect();
+        let w: f64 = average_ranks(&sizes)
+            .iter()
+            .zip(&diffs)
+            .filter(|&(_, &d)| d > 0.0)
+            .map(|(r, _)| r)
+            .sum();
+        let n = diffs.len();
+        let ties = tie_sum(&sizes);
+        if n > 0 && ties == 0.0 && n < EXACT_LIMIT {
+            return Ok(RankTest::exact(w, &signed_rank_pmf(n), alternative));
+        }
+        let fn_ = n as f64;
+        let mean = fn_ * (fn_ + 1.0) / 4.0;
+        let variance = fn_ * (fn_ + 1.0) * (2.0 * fn_ + 1.0) / 24.0 - ties / 48.0;
+        RankTest::normal(w, mean, variance, alternative)
+    })
+}
+
+/// Result of a Kolmogorov–Smirnov test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KsTest {
+    /// The statistic D: the largest absolute difference
+    /// between the two distribution functions compared.
+    pub statistic: f64,
+    /// Probability under the null hypothesis of a statistic
+    /// at least as large as that observed.
+    pub p_value: f64,
+    /// True if the p-value is exact, rather than from the
+    /// asymptotic distribution.
+    pub exact: bool,
+}
+
+impl KsTest {
+    /// Test result of NaNs, for NaNs propagated from the
+    /// input.
+    fn nan() -> Self {
+        KsTest {
+            statistic: f64::NAN,
+            p_value: f64::NAN,
+            exact: false,
+        }
+    }
+}
+
+/// Value of `n d^2` beyond which the exact one-sample tail
+/// is taken from the one-sided tail. Around it the two ways
+/// agree to about 1e-12; below it, both one-sided
+/// statistics reach `d` together too often, and far above
+/// it the complement of the CDF loses all precision.
+const KOLMOGOROV_TAIL: f64 = 5.0;
+
+/// Survival function of the Kolmogorov distribution, the
+/// limiting distribution of `sqrt(n) D` for the
+/// one-sample statistic `D` of a sample of size `n`.
+fn kolmogorov_sf(x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let mut sum = 0.0;
+    if x < 1.0 {
+        // This series for the CDF converges quickly for
+        // small x.
+        let c = -std::f64::consts::PI.powi(2) / (8.0 * x * x);
+        for j in (1..).step_by(2) {
+            let term = (c * (j * j) as f64).exp();
+            sum += term;
+            if term <= f64::EPSILON * sum {
+                break;
+            }
+        }
+        (1.0 - (2.0 * std::f64::consts::PI).sqrt() / x * sum).clamp(0.0, 1.0)
+    } else {
+        let mut sign = 1.0;
+        for j in 1.. {
+            let term = (-2.0 * (j * j) as f64 * x * x).exp();
+            sum += sign * term;
+            if term <= f64::EPSILON * sum {
+                break;
+            }
+            sign = -sign;
+        }
+        (2.0 * sum).clamp(0.0, 1.0)
+    }
+}
+
+/// Exact probability that the one-sample statistic of a
+/// sample of size `n` is less than `d`, by the method of
+/// Marsaglia, Tsang and Wang (2003).
+fn kolmogorov_cdf(n: usize, d: f64) -> f64 {
+    let nd = n as f64 * d;
+    let k = nd as usize + 1;
+    let m = 2 * k - 1;
+    let h = k as f64 - nd;
+    let mut hm = vec![vec![0.0; m]; m];
+    for (i, row) in hm.iter_mut().enumerate() {
+        for (j, x) in row.iter_mut().enumerate().take(i + 2) {
+            *x = 1.0;
+            if j == 0 {
+                *x -= h.powi(i as i32 + 1);
+            }
+            if i == m - 1 {
+                *x -= h.powi((m - j) as i32);
+            }
+        }
+    }
+    if 2.0 * h > 1.0 {
+        hm[m - 1][0] += (2.0 * h - 1.0).powi(m as i32);
+    }
+    for (i, row) in hm.iter_mut().enumerate() {
+        let mut factorial = 1.0;
+        for g in 1..=i + 1 {
+            factorial *= g as f64;
+            row[i + 1 - g] /= factorial;
+        }
+    }
+    let multiply = |a: &[Vec<f64>], b: &[Vec<f64>]| -> Vec<Vec<f64>> {
+        let mut c = vec![vec![0.0; m]; m];
+        for (ci, ai) in c.iter_mut().zip(a) {
+            for (&a_il, bl) in ai.iter().zip(b) {
+                for (c_ij, &b_lj) in ci.iter_mut().zip(bl) {
+                    *c_ij += a_il * b_lj;
+                }
+            }
+        }
+        c
+    };
+    // Raise the matrix to the n-th power by squaring.
+    let mut power: Option<Vec<Vec<f64>>> = None;
+    let mut base = hm;
+    let mut e = n;
+    while e > 0 {
+        if e % 2 == 1 {
+            power = Some(match power {
+                Some(p) => multiply(&p, &base),
+                None => base.clone(),
+            });
+        }
+        e /= 2;
+        if e > 0 {
+            base = multiply(&base, &base);
+        }
+    }
+    let mut p = power.map_or(1.0, |p| p[k - 1][k - 1]);
+    for i in 1..=n {
+        p *= i as f64 / n as f64;
+    }
+    p.clamp(0.0, 1.0)
+}
+
+/// Exact probability that the one-sided one-sample
+/// statistic `D+` of a sample of size `n` is at least `d`,
+/// by the formula of Birnbaum and Tingey (1951). Its terms
+/// are all positive, so small probabilities stay accurate.
+fn smirnov_one_sided_sf(n: usize, d: f64) -> f64 {
+    if d <= 0.0 {
+        return 1.0;
+    }
+    let fn_ = n as f64;
+    let ln_factorial = |k: f64| ln_gamma(k + 1.0);
+    let mut total = 0.0;
+    for j in 0..=(fn_ * (1.0 - d)).floor() as usize {
+        let fj = j as f64;
+        let ln_choose = ln_factorial(fn_) - ln_factorial(fj) - ln_factorial(fn_ - fj);
+        let below = (1.0 - d - fj / fn_).max(0.0);
+        total += (ln_choose + (fn_ - fj) * below.ln() + (fj - 1.0) * (d + fj / fn_).ln()).exp();
+    }
+    (d * total).clamp(0.0, 1.0)
+}
+
+/// Exact probability that the one-sample statistic of a
+/// sample of size `n` is at least `d`. Far enough into the
+/// tail that both one-sided statistics can hardly reach
+/// `d` together, this is twice the one-sided probability;
+/// elsewhere it is the complement of [`kolmogorov_cdf`].
+fn kolmogorov_exact_sf(n: usize, d: f64) -> f64 {
+    if n as f64 * d * d >= KOLMOGOROV_TAIL {
+        (2.0 * smirnov_one_sided_sf(n, d)).min(1.0)
+    } else {
+        1.0 - kolmogorov_cdf(n, d)
+    }
+}
+
+/// Exact probability that the two-sample statistic of
+/// samples of sizes `m` and `n` without ties is at least
+/// `d`. Counts the orderings of the samples whose empirical
+/// distributions get `d` apart directly, rather than as the
+/// complement of those that do not, so small probabilities
+/// stay accurate.
+fn smirnov_sf(m: usize, n: usize, d: f64) -> f64 {
+    let (m, n) = (m.min(n), m.max(n));
+    let (fm, fn_) = (m as f64, n as f64);
+    // The statistic is a multiple of 1 / (m n); this is
+    // safely between multiples.
+    let q = (0.5 + (d * fm * fn_ - 1e-7).floor()) / (fm * fn_);
+    // close[j] and apart[j] count the orderings of the first
+    // i values of one sample and j of the other whose
+    // empirical distributions have stayed within q, and
+    // those which have not. Both stay far below f64::MAX
+    // for the sizes tested exactly.
+    let mut close = vec![0.0; n + 1];
+    let mut apart = vec![0.0; n + 1];
+    close[0] = 1.0;
+    for i in 0..=m {
+        for j in 0..=n {
+            if i == 0 && j == 0 {
+                continue;
+            }
+            let (mut to_close, mut to_apart) = (close[j], apart[j]);
+            if j > 0 {
+                to_close += close[j - 1];
+                to_apart += apart[j - 1];
+            }
+            if (i as f64 / fm - j as f64 / fn_).abs() > q {
+                close[j] = 0.0;
+                apart[j] = to_close + to_apart;
+            } else {
+                close[j] = to_close;
+                apart[j] = to_apart;
+            }
+        }
+    }
+    apart[n] / (close[n] + apart[n])
+}
+
+/// One-sample Kolmogorov–Smirnov test of whether the input
+/// values are drawn from the distribution `dist`, which
+/// should be continuous and fixed in advance rather than
+/// fitted to the values. The statistic D is the largest
+/// absolute difference between the empirical distribution
+/// function of the values and that of `dist`. The p-value
+/// is two-sided: exact for fewer than 100 values, even far
+/// into the tail, and otherwise from the asymptotic
+/// Kolmogorov distribution.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [
+///     0.61, 0.29, 0.06, 0.59, -1.73, -0.74, 0.51, -0.56, 0.39, 1.64, 0.05, -0.06, 0.64,
+///     -0.82, 0.37, 1.77, 1.09, -1.28, 2.36, 1.31, 1.05, -0.32, -0.4, 1.06, -2.47,
+/// ];
+/// let ks = ks_one_sample(&nums, &Normal::standard(), NanPolicy::Error).unwrap();
+/// assert!((ks.statistic - 0.17409188119887736).abs() < 1e-14);
+/// assert!((ks.p_value - 0.389741171867918).abs() < 1e-12);
+/// assert!(ks.exact);
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums: Vec<f64> = (0..200).map(|i| (i as f64 + 0.5) / 200.0).collect();
+/// let uniform = Uniform::new(0.0, 1.0).unwrap();
+/// let ks = ks_one_sample(&nums, &uniform, NanPolicy::Error).unwrap();
+/// assert!((ks.statistic - 0.0025).abs() < 1e-15);
+/// assert_eq!((1.0, false), (ks.p_value, ks.exact));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums: Vec<f64> = (0..20).map(|i| i as f64 / 200.0).collect();
+/// let uniform = Uniform::new(0.0, 1.0).unwrap();
+/// let ks = ks_one_sample(&nums, &uniform, NanPolicy::Error).unwrap();
+/// assert!((ks.statistic - 0.905).abs() < 1e-15);
+/// assert!((ks.p_value / 7.170651157524861e-21 - 1.0).abs() < 1e-12);
+/// ```
+pub fn ks_one_sample<T: Numeric, D: Distribution>(
+    nums: &[T],
+    dist: &D,
+    nan: NanPolicy,
+) -> StatResult<KsTest> {
+    nan.stat_or(nums, KsTest::nan(), |nums| {
+        let ecdf = Ecdf::new(nums, NanPolicy::Error)?;
+        let values = ecdf.values();
+        let n = values.len();
+        let mut d: f64 = 0.0;
+        for (i, &x) in values.iter().enumerate() {
+            let f = finite(dist.cdf(x))?;
+            d = d
+                .max((i + 1) as f64 / n as f64 - f)
+                .max(f - i as f64 / n as f64);
+        }
+        let exact = n < 100;
+        let p_value = if exact {
+            kolmogorov_exact_sf(n, d)
+        } else {
+            kolmogorov_sf((n as f64).sqrt() * d)
+        };
+        Ok(KsTest {
+            statistic: d,
+            p_value,
+            exact,
+        })
+    })
+}
+
+/// Two-sample Kolmogorov–Smirnov test of whether the
+/// independent samples `xs` and `ys` come from the same
+/// continuous distribution. The statistic D is the largest
+/// absolute difference between their empirical
+/// distribution functions. The p-value is two-sided: exact
+/// for samples whose sizes have a product less than 10000
+/// and without ties, and otherwise from the asymptotic
+/// Kolmogorov distribution. Each sample needs at least one
+/// value; NaN handling applies to each separately.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let xs = [1.2, 3.4, 0.5, 2.2, 4.1];
+/// let ys = [2.9, 5.5, 6.1, 3.8];
+/// let ks = ks_two_sample(&xs, &ys, NanPolicy::Error).unwrap();
+/// assert!((ks.statistic - 0.6).abs() < 1e-15);
+/// assert!((ks.p_value - 2.0 / 7.0).abs() < 1e-15);
+/// assert!(ks.exact);
+/// ```
+/// ```
+/// # use stats::*;
+/// let xs: Vec<f64> = (0..120).map(|i| (i % 12) as f64).collect();
+/// let ys: Vec<f64> = (0..100).map(|i| (i % 10) as f64 + 1.0).collect();
+/// let ks = ks_two_sample(&xs, &ys, NanPolicy::Error).unwrap();
+/// assert!((ks.statistic - 1.0 / 12.0).abs() < 1e-15);
+/// assert!(!ks.exact && ks.p_value > 0.5);
+/// ```
+/// ```
+/// # use stats::*;
+/// let xs: Vec<f64> = (0..40).map(|i| i as f64).collect();
+/// let ys: Vec<f64> = (40..80).map(|i| i as f64).collect();
+/// let ks = ks_two_sample(&xs, &ys, NanPolicy::Error).unwrap();
+/// assert_eq!((1.0, true), (ks.statistic, ks.exact));
+/// assert!((ks.p_value / 1.8603403656036264e-23 - 1.0).abs() < 1e-12);
+/// ```
+pub fn ks_two_sample<T: Numeric, U: Numeric>(
+    xs: &[T],
+    ys: &[U],
+    nan: NanPolicy,
+) -> StatResult<KsTest> {
+    nan.stat_samples_or(xs, ys, KsTest::nan(), |xs, ys| {
+        let ecdf_x = Ecdf::new(xs, NanPolicy::Error)?;
+        let ecdf_y = Ecdf::new(ys, NanPolicy::Error)?;
+        let d = ecdf_x
+            .values()
+            .iter()
+            .chain(ecdf_y.values())
+            .map(|&v| (ecdf_x.cdf(v) - ecdf_y.cdf(v)).abs())
+            .fold(0.0, f64::max);
+        let (m, n) = (xs.len(), ys.len());
+        let exact = m * n < 10000 && tie_sum(&[xs, ys].concat()) == 0.0;
+        let p_value = if exact {
+            smirnov_sf(m, n, d)
+        } else {
+            let (fm, fn_) = (m as f64, n as f64);
+            kolmogorov_sf((fm * fn_ / (fm + fn_)).sqrt() * d)
+        };
+        Ok(KsTest {
+            statistic: d,
+            p_value,
+            exact,
+        })
+    })
+}
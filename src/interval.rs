@@ -0,0 +1,192 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Confidence intervals.
+
+use crate::distributions::*;
+use crate::error::*;
+use crate::moments::*;
+use crate::nan::*;
+use crate::num::*;
+use crate::order::*;
+
+/// A confidence interval: a range of values, computed from
+/// a sample, that covers the true value of some parameter
+/// with probability `level` over repeated sampling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    /// Lower bound.
+    pub lower: f64,
+    /// Upper bound.
+    pub upper: f64,
+    /// Confidence level, between 0 and 1.
+    pub level: f64,
+}
+
+impl Interval {
+    /// Interval of NaNs, for NaNs propagated from the
+    /// input.
+    pub(crate) fn nan(level: f64) -> Self {
+        Interval {
+            lower: f64::NAN,
+            upper: f64::NAN,
+            level,
+        }
+    }
+
+    /// Interval of half-width `half_width` around
+    /// `estimate`.
+    pub(crate) fn around(estimate: f64, half_width: f64, level: f64) -> StatResult<Self> {
+        Ok(Interval {
+            lower: finite(estimate - half_width)?,
+            upper: finite(estimate + half_width)?,
+            level,
+        })
+    }
+
+    /// Width of the interval.
+    pub fn width(&self) -> f64 {
+        self.upper - self.lower
+    }
+
+    /// True if `x` lies in the interval, including its
+    /// bounds.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use stats::*;
+    /// let ci = Interval { lower: 1.0, upper: 2.0, level: 0.95 };
+    /// assert!(ci.contains(1.0) && !ci.contains(2.5));
+    /// assert_eq!(1.0, ci.width());
+    /// ```
+    pub fn contains(&self, x: f64) -> bool {
+        (self.lower..=self.upper).contains(&x)
+    }
+}
+
+/// Check that a confidence level is strictly between 0 and
+/// 1.
+pub(crate) fn check_level(level: f64) -> StatResult<()> {
+    if level > 0.0 && level < 1.0 {
+        Ok(())
+    } else {
+        Err(StatError::InvalidParameter(
+            "confidence level must be between 0 and 1",
+        ))
+    }
+}
+
+/// Confidence interval for the mean of the population
+/// from which the input values are drawn, with confidence
+/// `level` between 0 and 1. Uses Student's t distribution,
+/// so assumes the values are roughly normal or numerous.
+/// At least two values are needed.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let ci = mean_ci(&[1.0, 2.0, 3.0, 4.0, 5.0], 0.95, NanPolicy::Error).unwrap();
+/// assert!((ci.lower - 1.0367568385224).abs() < 1e-12);
+/// assert!((ci.upper - 4.9632431614776).abs() < 1e-12);
+/// ```
+/// ```
+/// # use stats::*;
+/// let err = StatError::TooFewSamples { needed: 2, got: 1 };
+/// assert_eq!(Err(err), mean_ci(&[1.0], 0.95, NanPolicy::Error));
+/// ```
+pub fn mean_ci<T: Numeric>(nums: &[T], level: f64, nan: NanPolicy) -> StatResult<Interval> {
+    check_level(level)?;
+    nan.stat_or(nums, Interval::nan(level), |nums| {
+        let moments: Moments = nums.iter().collect();
+        let sd = moments.stddev(1)?;
+        let mean = moments.mean()?;
+        let t = StudentsT::new((nums.len() - 1) as f64)?;
+        let se = sd / (nums.len() as f64).sqrt();
+        Interval::around(mean, t.inverse_cdf(0.5 + 0.5 * level) * se, level)
+    })
+}
+
+/// Distribution-free confidence interval for the median of
+/// the population from which the input values are drawn,
+/// with confidence at least `level` between 0 and 1. The
+/// bounds are order statistics of the values, chosen
+/// symmetrically from the binomial distribution of the
+/// number of values below the median. Too few values give
+/// no interval with the requested confidence.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [10.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0, 1.0];
+/// let ci = median_ci(&nums, 0.95, NanPolicy::Error).unwrap();
+/// assert_eq!((2.0, 9.0), (ci.lower, ci.upper));
+/// ```
+/// ```
+/// # use stats::*;
+/// let err = StatError::TooFewSamples { needed: 6, got: 5 };
+/// let nums = [1.0, 2.0, 3.0, 4.0, 5.0];
+/// assert_eq!(Err(err), median_ci(&nums, 0.95, NanPolicy::Error));
+/// ```
+pub fn median_ci<T: Numeric>(nums: &[T], level: f64, nan: NanPolicy) -> StatResult<Interval> {
+    check_level(level)?;
+    nan.stat_or(nums, Interval::nan(level), |nums| {
+        let n = nums.len();
+        if n == 0 {
+            return Err(StatError::Empty);
+        }
+        let alpha = 1.0 - level;
+        // The interval from the l-th to the (n + 1 - l)-th
+        // smallest values, counting from 1, misses the
+        // median with probability 2 P(B < l) for B binomial
+        // with n trials and probability one half.
+        let l = Binomial::new(n as u64, 0.5)?.inverse_cdf(0.5 * alpha) as usize;
+        if l == 0 {
+            // 2^-n must be less than alpha / 2.
+            let needed = (2.0 / alpha).log2().floor() as usize + 1;
+            return Err(StatError::TooFewSamples { needed, got: n });
+        }
+        let mut nums = nums.to_owned();
+        nums.sort_unstable_by(cmp_f64);
+        Ok(Interval {
+            lower: nums[l - 1],
+            upper: nums[n - l],
+            level,
+        })
+    })
+}
+
+/// Confidence interval for the variance of the population
+/// from which the input values are drawn, with confidence
+/// `level` between 0 and 1. Uses the chi-squared
+/// distribution of the sample variance, which holds only
+/// for normally distributed values. At least two values are
+/// needed.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let ci = variance_ci(&[1.0, 2.0, 3.0, 4.0, 5.0], 0.95, NanPolicy::Error).unwrap();
+/// assert!((ci.lower - 0.8974012960218245).abs() < 1e-14);
+/// assert!((ci.upper - 20.643304955356687).abs() < 1e-12);
+/// ```
+pub fn variance_ci<T: Numeric>(nums: &[T], level: f64, nan: NanPolicy) -> StatResult<Interval> {
+    check_level(level)?;
+    nan.stat_or(nums, Interval::nan(level), |nums| {
+        let moments: Moments = nums.iter().collect();
+        let variance = moments.variance(1)?;
+        let df = (nums.len() - 1) as f64;
+        let chi2 = ChiSquared::new(df)?;
+        let alpha = 1.0 - level;
+        Ok(Interval {
+            lower: finite(df * variance / chi2.inverse_cdf(1.0 - 0.5 * alpha))?,
+            upper: finite(df * variance / chi2.inverse_cdf(0.5 * alpha))?,
+            level,
+        })
+    })
+}
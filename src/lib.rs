@@ -3,12 +3,72 @@
 // Please see the file LICENSE in the source
 // distribution of this software for license terms.
 
-///! Functions to compute various statistics on a slice of
-///! floating-point numbers.
+//! Functions to compute various statistics on a slice of
+//! numbers. The statistics here accept any [`Numeric`]
+//! type, such as `f32` or `i64`, without copying; integers
+//! are added up exactly.
+//!
+//! Each statistic comes in two forms: a function returning
+//! `Option`, which is `None` when the statistic is
+//! ill-defined, and a `try_` function returning a
+//! [`StatResult`] that says why. The `try_` functions also
+//! take a [`NanPolicy`] saying how to treat NaNs in the
+//! input; the `Option` functions use the default policy,
+//! under which any NaN makes the statistic ill-defined.
+
+mod bivariate;
+mod distributions;
+mod ecdf;
+mod error;
+mod freq;
+mod histogram;
+mod hypothesis;
+mod interval;
+mod means;
+mod moments;
+mod nan;
+mod norm;
+mod num;
+mod order;
+mod quantile;
+mod rank;
+mod regression;
+mod robust;
+mod shape;
+mod special;
+mod sum;
+mod weighted;
+
+pub use bivariate::*;
+pub use distributions::*;
+pub use ecdf::*;
+pub use error::*;
+pub use freq::*;
+pub use histogram::*;
+pub use hypothesis::*;
+pub use interval::*;
+pub use means::*;
+pub use moments::*;
+pub use nan::*;
+pub use norm::*;
+pub use num::*;
+pub use order::*;
+pub use quantile::*;
+pub use rank::*;
+pub use regression::*;
+pub use robust::*;
+pub use shape::*;
+pub use special::*;
+pub use sum::*;
+pub use weighted::*;
 
 /// Type of statistics function. If the statistic
 /// is ill-defined, `None` will be returned.
-pub type StatFn = fn(&[f64]) -> Option<f64>;
+pub type StatFn<T = f64> = fn(&[T]) -> Option<f64>;
+
+/// Type of statistics function reporting errors. If the
+/// statistic is ill-defined, the reason will be returned.
+pub type TryStatFn<T = f64> = fn(&[T], NanPolicy) -> StatResult;
 
 /// Arithmetic mean of input values. The mean of an empty
 /// list is 0.0.
@@ -17,7 +77,7 @@ pub type StatFn = fn(&[f64]) -> Option<f64>;
 ///
 /// ```
 /// # use stats::*;
-/// assert_eq!(Some(0.0), mean(&[]));
+/// assert_eq!(Some(0.0), mean::<f64>(&[]));
 /// ```
 /// ```
 /// # use stats::*;
@@ -39,53 +99,190 @@ pub type StatFn = fn(&[f64]) -> Option<f64>;
 /// # use stats::*;
 /// assert_eq!(Some(-1.6), mean(&[-1.0, 1.0, -7.0, 2.0, -3.0]));
 /// ```
-pub fn mean(nums: &[f64]) -> Option<f64> {
-    if nums == [] {
-        Some(0.0)
-    } else {
-        let mut x: f64 = 0.0;
-        let mut y: f64 = 0.0;
-        for i in nums {
-            x += 1.0;
-            y += i;
-        }
+pub fn mean<T: Numeric>(nums: &[T]) -> Option<f64> {
+    try_mean(nums, Summation::default(), NanPolicy::default()).ok()
+}
 
-        let z: f64 = y / x;
-        Some(z)
+/// Arithmetic mean of input values, as for [`mean`],
+/// adding them up with the given strategy. Integer values
+/// are instead added up exactly. The mean of an empty list
+/// is 0.0.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(0.0), try_mean::<f64>(&[], Summation::Neumaier, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(0.5), try_mean(&[-3.0, -1.0, 1.0, 5.0], Summation::Neumaier, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Err(StatError::NanInput), try_mean(&[1.0, std::f64::NAN], Summation::Neumaier, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Err(StatError::NonFinite), try_mean(&[1.0, std::f64::INFINITY], Summation::Neumaier, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(2.0), try_mean(&[1.0, std::f64::NAN, 3.0], Summation::Neumaier, NanPolicy::Skip));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1e100, 1.0, -1e100, 2.0];
+/// assert_eq!(Ok(0.5), try_mean(&nums, Summation::Naive, NanPolicy::Error));
+/// assert_eq!(Ok(0.75), try_mean(&nums, Summation::Exact, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1e308, 1e308];
+/// assert_eq!(Ok(1e308), try_mean(&nums, Summation::Exact, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums: [i64; 3] = [i64::MAX, i64::MAX, -3];
+/// let expected = (2.0 * i64::MAX as f64 - 3.0) / 3.0;
+/// assert_eq!(Ok(expected), try_mean(&nums, Summation::Naive, NanPolicy::Error));
+/// ```
+pub fn try_mean<T: Numeric>(nums: &[T], summation: Summation, nan: NanPolicy) -> StatResult {
+    if let Some(total) = T::exact_sum(nums) {
+        if nums.is_empty() {
+            return Ok(0.0);
+        }
+        return finite(total / nums.len() as f64);
     }
+    nan.stat_values(nums, |values| {
+        let n = values.clone().count();
+        if n == 0 {
+            return Ok(0.0);
+        }
+        let total = summation.sum_iter(values.clone());
+        if total.is_finite() {
+            Ok(total / n as f64)
+        } else {
+            // The sum may have overflowed: Welford's update
+            // avoids forming it.
+            values.collect::<Moments>().mean()
+        }
+    })
 }
 
-/// Population standard deviation of input values. The
-/// standard deviation of an empty list is undefined.
+/// Variance of input values with `ddof` delta degrees of
+/// freedom: the sum of squared deviations from the mean is
+/// divided by `n - ddof`. Use 0 for the population
+/// variance and 1 for the sample variance. The variance of
+/// a list with no more than `ddof` values is undefined.
 ///
 /// # Examples:
 ///
 /// ```
 /// # use stats::*;
-/// assert_eq!(None, stddev(&[]));
+/// assert_eq!(None, variance::<f64>(&[], 0));
 /// ```
 /// ```
 /// # use stats::*;
-/// assert_eq!(Some(0.0), stddev(&[1.0, 1.0]));
+/// assert_eq!(None, variance(&[1.0], 1));
 /// ```
 /// ```
 /// # use stats::*;
-/// assert_eq!(Some(4.5), stddev(&[2.0, -1.0]));
+/// assert_eq!(Some(2.25), variance(&[2.0, -1.0], 0));
 /// ```
 /// ```
 /// # use stats::*;
-/// assert_eq!(Some(12.0), stddev(&[1.0, 1.0, -5.0]));
+/// assert_eq!(Some(4.5), variance(&[2.0, -1.0], 1));
 /// ```
 /// ```
 /// # use stats::*;
-/// assert_eq!(Some(28.25), stddev(&[1.0, 1.0, -5.0, -10.0]));
+/// assert_eq!(Some(12.0), variance(&[1.0, 1.0, -5.0], 1));
 /// ```
-pub fn stddev(nums: &[f64]) -> Option<f64> {
-    if nums == [] {
-        None
-    } else {
-        Some(summation_power(nums, mean(nums).unwrap()) / (nums.len() - 1) as f64)
-    }
+/// ```
+/// # use stats::*;
+/// assert_eq!(Some(28.25), variance(&[1.0, 1.0, -5.0, -10.0], 1));
+/// ```
+pub fn variance<T: Numeric>(nums: &[T], ddof: usize) -> Option<f64> {
+    try_variance(nums, ddof, NanPolicy::default()).ok()
+}
+
+/// Variance of input values, as for [`variance`].
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(Err(StatError::Empty), try_variance::<f64>(&[], 1, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(
+///     Err(StatError::TooFewSamples { needed: 2, got: 1 }),
+///     try_variance(&[1.0], 1, NanPolicy::Error),
+/// );
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(0.0), try_variance(&[1.0], 0, NanPolicy::Error));
+/// ```
+pub fn try_variance<T: Numeric>(nums: &[T], ddof: usize, nan: NanPolicy) -> StatResult {
+    let mut moments = Moments::with_nan_policy(nan);
+    moments.extend(nums.iter().map(|x| x.to_f64()));
+    moments.variance(ddof)
+}
+
+/// Standard deviation of input values with `ddof` delta
+/// degrees of freedom, as for [`variance`]. Use 0 for the
+/// population standard deviation and 1 for the sample
+/// standard deviation.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(None, stddev::<f64>(&[], 0));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Some(0.0), stddev(&[1.0, 1.0], 1));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Some(1.5), stddev(&[2.0, -1.0], 0));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Some(2.0), stddev(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0], 0));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Some(3.0), stddev(&[1.0, 4.0, 7.0], 1));
+/// ```
+pub fn stddev<T: Numeric>(nums: &[T], ddof: usize) -> Option<f64> {
+    try_stddev(nums, ddof, NanPolicy::default()).ok()
+}
+
+/// Standard deviation of input values, as for [`stddev`].
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(Err(StatError::Empty), try_stddev::<f64>(&[], 0, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(
+///     Err(StatError::TooFewSamples { needed: 2, got: 1 }),
+///     try_stddev(&[1.0], 1, NanPolicy::Error),
+/// );
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(4.0), try_stddev(&[1.0, 5.0, 9.0], 1, NanPolicy::Error));
+/// ```
+pub fn try_stddev<T: Numeric>(nums: &[T], ddof: usize, nan: NanPolicy) -> StatResult {
+    try_variance(nums, ddof, nan).map(f64::sqrt)
 }
 
 /// Median value of input values, taking the value closer
@@ -96,7 +293,7 @@ pub fn stddev(nums: &[f64]) -> Option<f64> {
 ///
 /// ```
 /// # use stats::*;
-/// assert_eq!(None, median(&[]));
+/// assert_eq!(None, median::<f64>(&[]));
 /// ```
 /// ```
 /// # use stats::*;
@@ -118,27 +315,41 @@ pub fn stddev(nums: &[f64]) -> Option<f64> {
 /// # use stats::*;
 /// assert_eq!(Some(-0.2), median(&[1.2, 0.0, -1.0, 1.0, 5.0, -3.0, -0.2, -0.5]));
 /// ```
-pub fn median(nums: &[f64]) -> Option<f64> {
-    // Make a sorted copy of the input floats.
-    let mut nums = nums.to_owned();
-    // https://users.rust-lang.org/t/how-to-sort-a-vec-of-floats/2838/2
-    nums.sort_by(|a, b| a.partial_cmp(b).unwrap());
-
-    if nums == [] {
-        None
-    } else {
-        let length = nums.len();
-        let offset = length - 1;
-        if length % 2 == 0 {
-            let first = nums[offset / 2];
-            //From the notes above the examples we are not dividing by 2.
-            //let second = nums[(offset / 2) + 1];
-            //Some((first + second) / 2.0)
-            Some(first)
-        } else {
-            Some(nums[offset / 2])
-        }
-    }
+pub fn median<T: Numeric>(nums: &[T]) -> Option<f64> {
+    try_median(nums, NanPolicy::default()).ok()
+}
+
+/// Median value of input values, as for [`median`]. Takes
+/// expected linear time; see [`median_mut`] to also avoid
+/// copying the input.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(Err(StatError::Empty), try_median::<f64>(&[], NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(0.0), try_median(&[0.0, -1.0, 1.0], NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Err(StatError::NanInput), try_median(&[0.0, std::f64::NAN, 1.0], NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(0.5), try_median(&[0.5, std::f64::NAN, 1.0, -1.0], NanPolicy::Skip));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert!(try_median(&[0.0, std::f64::NAN, 1.0], NanPolicy::Propagate).unwrap().is_nan());
+/// ```
+pub fn try_median<T: Numeric>(nums: &[T], nan: NanPolicy) -> StatResult {
+    median_mut(
+        &mut nums.iter().map(|x| x.to_f64()).collect::<Vec<f64>>(),
+        nan,
+    )
 }
 
 /// L2 norm (Euclidean norm) of input values. The L2
@@ -148,7 +359,7 @@ pub fn median(nums: &[f64]) -> Option<f64> {
 ///
 /// ```
 /// # use stats::*;
-/// assert_eq!(Some(0.0), l2(&[]));
+/// assert_eq!(Some(0.0), l2::<f64>(&[]));
 /// ```
 /// ```
 /// # use stats::*;
@@ -170,12 +381,31 @@ pub fn median(nums: &[f64]) -> Option<f64> {
 /// # use stats::*;
 /// assert_eq!(Some(8.0), l2(&[-3.0, 4.0, -3.0, 5.0, 1.0, -2.0]));
 /// ```
-pub fn l2(nums: &[f64]) -> Option<f64> {
-    if nums == [] {
-        Some(0.0)
-    } else {
-        Some(summation_power(nums, 0.0).sqrt())
-    }
+pub fn l2<T: Numeric>(nums: &[T]) -> Option<f64> {
+    try_l2(nums, Summation::default(), NanPolicy::default()).ok()
+}
+
+/// L2 norm (Euclidean norm) of input values, as for
+/// [`l2`], adding up the squares with the given strategy.
+/// The L2 norm of an empty list is 0.0.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(0.0), try_l2::<f64>(&[], Summation::Neumaier, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(5.0), try_l2(&[-3.0, 4.0], Summation::Neumaier, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let norm = try_l2(&[1e200, 1e200], Summation::Neumaier, NanPolicy::Error).unwrap();
+/// assert!((norm - 2f64.sqrt() * 1e200).abs() < 1e185);
+/// ```
+pub fn try_l2<T: Numeric>(nums: &[T], summation: Summation, nan: NanPolicy) -> StatResult {
+    try_lp(nums, 2.0, summation, nan)
 }
 
 /// This takes each array value, minuses it from the offset,
@@ -185,7 +415,7 @@ pub fn l2(nums: &[f64]) -> Option<f64> {
 ///
 /// ```
 /// # use stats::*;
-/// assert_eq!(0.0, summation_power(&[], 0.0));
+/// assert_eq!(0.0, summation_power::<f64>(&[], 0.0));
 /// ```
 /// ```
 /// # use stats::*;
@@ -199,14 +429,53 @@ pub fn l2(nums: &[f64]) -> Option<f64> {
 /// # use stats::*;
 /// assert_eq!(29.0, summation_power(&[-3.0, 4.0], 2.0));
 /// ```
-pub fn summation_power(nums: &[f64], offset: f64) -> f64 {
-    //took this to_owned form the median section
-    let mut nums = nums.to_owned();
-    let mut total: f64 = 0.0;
+pub fn summation_power<T: Numeric>(nums: &[T], offset: f64) -> f64 {
+    summation_nth_power(nums, offset, 2)
+}
 
-    for i in &mut nums {
-        total += (*i - offset).powf(2.0);
-    }
+/// Sum of the `k`-th powers of the differences of input
+/// values from `offset`. With the mean as the offset this
+/// is `n` times the `k`-th central moment.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(-27.0, summation_nth_power(&[-3.0], 0.0, 3));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(summation_power(&[-3.0, 4.0], 2.0), summation_nth_power(&[-3.0, 4.0], 2.0, 2));
+/// ```
+pub fn summation_nth_power<T: Numeric>(nums: &[T], offset: f64, k: i32) -> f64 {
+    Summation::default().sum_iter(nums.iter().map(|i| (i.to_f64() - offset).powi(k)))
+}
 
-    total
+/// Sum of squared differences of input values from
+/// `offset`, as for [`summation_power`], adding them up
+/// with the given strategy.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [-3.0, 4.0];
+/// let total = try_summation_power(&nums, 2.0, Summation::Exact, NanPolicy::Error);
+/// assert_eq!(Ok(29.0), total);
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1e200, 1e200];
+/// let total = try_summation_power(&nums, 0.0, Summation::Exact, NanPolicy::Error);
+/// assert_eq!(Err(StatError::NonFinite), total);
+/// ```
+pub fn try_summation_power<T: Numeric>(
+    nums: &[T],
+    offset: f64,
+    summation: Summation,
+    nan: NanPolicy,
+) -> StatResult {
+    nan.stat_values(nums, |values| {
+        finite(summation.sum_iter(values.map(|i| (i - offset).powf(2.0))))
+    })
 }
@@ -3,56 +3,496 @@
 // Please see the file LICENSE in the source
 // distribution of this software for license terms.
 
+//! Compute a statistic on numbers presented one-per-line on
+//! standard input.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
 use std::process::exit;
 
-///! Compute a statistic on numbers presented one-per-line on
-///! standard input.
+use stats::{
+    Alternative, Bias, BinRule, CoMoments, Histogram, Interval, Moments, NanPolicy, QuantileMethod,
+    StatError, StatResult, Summation, WeightKind,
+};
+
+/// A statistic is either computed from a `Moments` or
+/// `CoMoments` accumulator in constant memory, or from the
+/// whole input at once.
+#[derive(Clone)]
+enum Stat {
+    Streaming(fn(&Moments) -> StatResult),
+    Bivariate(fn(&CoMoments) -> StatResult),
+    Slice(stats::TryStatFn),
+    Param(ParamFn, f64),
+    Quantiles(Vec<f64>),
+    Table(TableFn),
+    Histogram,
+    Regress,
+    TTest(String, String),
+    MannWhitney(String, String),
+}
+
+/// Type of statistic with a numeric parameter.
+type ParamFn = fn(&[f64], f64, NanPolicy) -> StatResult;
+
+/// Type of statistic giving a table of values and their
+/// counts, binned with a tolerance.
+type TableFn = fn(&[f64], f64, NanPolicy) -> StatResult<Vec<(f64, usize)>>;
+
+/// Type of statistic computed from values and their
+/// weights.
+type WeightedFn = fn(&[f64], &[f64], WeightKind, NanPolicy) -> StatResult;
+
+/// Type of confidence interval for a statistic, with a
+/// confidence level.
+type CiFn = fn(&[f64], f64, NanPolicy) -> StatResult<Interval>;
 
 /// Report proper usage and exit.
 fn usage() -> ! {
-    eprintln!("stats: usage: stats [--mean|--stddev|--median|--l2]");
+    eprintln!("stats: usage: stats [--nan=skip|error|propagate] [--ci LEVEL] regress");
+    eprintln!("  or: stats [--nan=skip|error|propagate]");
+    eprintln!("  [--alternative=two-sided|less|greater] ttest|mannwhitney FILE_A FILE_B");
+    eprintln!("  or: stats [--nan=skip|error|propagate] [--ci LEVEL]");
+    eprintln!("  [--weighted[=frequency|reliability]] [--tolerance=W]");
+    eprintln!("  [--bins=N|sturges|scott|fd|sqrt] STAT");
+    eprintln!("  where STAT is one of --mean, --variance, --sample-variance,");
+    eprintln!("  --stddev, --population-stddev, --sample-stddev, --skewness,");
+    eprintln!("  --kurtosis, --median, --mad, --iqr, --mode, --freq,");
+    eprintln!("  --histogram, --l2, --cov, --corr,");
+    eprintln!("  --geometric-mean, --harmonic-mean, --power-mean P,");
+    eprintln!("  --trimmed-mean F, --winsorized-mean F,");
+    eprintln!("  --quantile P[,P...] or --percentile P[,P...]");
+    eprintln!("  With --weighted, each input line is a value and its weight.");
+    eprintln!("  With --cov, --corr or regress, each input line is a pair of values.");
+    eprintln!("  --ci applies to --mean, --median, the variances and standard");
+    eprintln!("  deviations, and regress.");
     exit(1);
 }
 
+/// Parse a comma-separated list of numbers, scaling each
+/// down by `scale`.
+fn parse_list(list: &str, scale: f64) -> Vec<f64> {
+    list.split(',')
+        .map(|s| {
+            s.parse::<f64>().map(|x| x / scale).unwrap_or_else(|e| {
+                eprintln!("stats: error parsing {}: {}", s, e);
+                usage();
+            })
+        })
+        .collect()
+}
+
+/// Iterate over the lines of `input`, exiting with an
+/// error message on a read error.
+fn read_lines_from(input: impl BufRead) -> impl Iterator<Item = String> {
+    input.lines().map(|s| {
+        s.unwrap_or_else(|e| {
+            eprintln!("error reading input: {}", e);
+            exit(-1);
+        })
+    })
+}
+
+/// Iterate over the lines of standard input, exiting with
+/// an error message on a read error.
+fn read_lines() -> impl Iterator<Item = String> {
+    read_lines_from(std::io::stdin().lock())
+}
+
+/// Parse a number, exiting with an error message on bad
+/// input.
+fn parse_num(s: &str) -> f64 {
+    s.parse::<f64>().unwrap_or_else(|e| {
+        eprintln!("error parsing number {}: {}", s, e);
+        exit(-1);
+    })
+}
+
+/// Iterate over the numbers on standard input, exiting
+/// with an error message on bad input.
+fn read_nums() -> impl Iterator<Item = f64> {
+    read_lines().map(|s| parse_num(&s))
+}
+
+/// Read the numbers, one per line, in the file at `path`,
+/// exiting with an error message on bad input.
+fn read_file_nums(path: &str) -> Vec<f64> {
+    let file = File::open(path).unwrap_or_else(|e| {
+        eprintln!("error opening {}: {}", path, e);
+        exit(-1);
+    });
+    read_lines_from(BufReader::new(file))
+        .map(|s| parse_num(&s))
+        .collect()
+}
+
+/// Iterate over the pairs of numbers, one pair per line,
+/// on standard input, exiting with an error message on bad
+/// input.
+fn read_pair_iter() -> impl Iterator<Item = (f64, f64)> {
+    read_lines().map(|s| {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        if fields.len() != 2 {
+            eprintln!("error parsing pair {}: expected two numbers", s);
+            exit(-1);
+        }
+        (parse_num(fields[0]), parse_num(fields[1]))
+    })
+}
+
+/// Read the pairs of numbers, one pair per line, on
+/// standard input, exiting with an error message on bad
+/// input.
+fn read_pairs() -> (Vec<f64>, Vec<f64>) {
+    read_pair_iter().unzip()
+}
+
+/// Confidence interval for the standard deviation, from
+/// that for the variance.
+fn stddev_ci(nums: &[f64], level: f64, nan: NanPolicy) -> StatResult<Interval> {
+    stats::variance_ci(nums, level, nan).map(|ci| Interval {
+        lower: ci.lower.sqrt(),
+        upper: ci.upper.sqrt(),
+        ..ci
+    })
+}
+
+/// Compute `stat` on `nums`, followed by the confidence
+/// interval `ci` at `level`.
+fn with_ci(
+    stat: Stat,
+    ci: CiFn,
+    level: f64,
+    nums: &[f64],
+    nan: NanPolicy,
+) -> StatResult<Vec<String>> {
+    let estimate = match stat {
+        Stat::Streaming(f) => {
+            let mut moments = Moments::with_nan_policy(nan);
+            moments.extend(nums);
+            f(&moments)?
+        }
+        Stat::Slice(f) => f(nums, nan)?,
+        _ => unreachable!("statistic has no confidence interval"),
+    };
+    let interval = ci(nums, level, nan)?;
+    Ok(vec![format!(
+        "{}\t{}\t{}",
+        estimate, interval.lower, interval.upper
+    )])
+}
+
 /// Do the computation.
 fn main() {
-    // Process the argument.
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() != 2 {
-        usage();
-    }
-    let target = &args[1];
-    let argdescs: &[(&str, stats::StatFn)] = &[
-        ("--mean", stats::mean),
-        ("--stddev", stats::stddev),
-        ("--median", stats::median),
-        ("--l2", stats::l2),
+    // Process the arguments.
+    let argdescs: &[(&str, Stat, Option<WeightedFn>)] = &[
+        (
+            "--mean",
+            Stat::Streaming(|m| match m.mean() {
+                Err(StatError::Empty) => Ok(0.0),
+                r => r,
+            }),
+            Some(|xs, ws, _, nan| stats::weighted_mean(xs, ws, nan)),
+        ),
+        ("--geometric-mean", Stat::Slice(stats::geometric_mean), None),
+        ("--harmonic-mean", Stat::Slice(stats::harmonic_mean), None),
+        (
+            "--variance",
+            Stat::Streaming(|m| m.variance(0)),
+            Some(|xs, ws, kind, nan| stats::weighted_variance(xs, ws, kind, 0, nan)),
+        ),
+        (
+            "--sample-variance",
+            Stat::Streaming(|m| m.variance(1)),
+            Some(|xs, ws, kind, nan| stats::weighted_variance(xs, ws, kind, 1, nan)),
+        ),
+        (
+            "--stddev",
+            Stat::Streaming(|m| m.stddev(0)),
+            Some(|xs, ws, kind, nan| stats::weighted_variance(xs, ws, kind, 0, nan).map(f64::sqrt)),
+        ),
+        (
+            "--population-stddev",
+            Stat::Streaming(|m| m.stddev(0)),
+            Some(|xs, ws, kind, nan| stats::weighted_variance(xs, ws, kind, 0, nan).map(f64::sqrt)),
+        ),
+        (
+            "--sample-stddev",
+            Stat::Streaming(|m| m.stddev(1)),
+            Some(|xs, ws, kind, nan| stats::weighted_variance(xs, ws, kind, 1, nan).map(f64::sqrt)),
+        ),
+        (
+            "--skewness",
+            Stat::Streaming(|m| m.skewness(Bias::Corrected)),
+            None,
+        ),
+        (
+            "--kurtosis",
+            Stat::Streaming(|m| m.excess_kurtosis(Bias::Corrected)),
+            None,
+        ),
+        (
+            "--median",
+            Stat::Slice(stats::try_median),
+            Some(|xs, ws, _, nan| stats::weighted_median(xs, ws, nan)),
+        ),
+        ("--mad", Stat::Slice(stats::scaled_mad), None),
+        (
+            "--iqr",
+            Stat::Slice(|nums, nan| stats::iqr(nums, QuantileMethod::default(), nan)),
+            None,
+        ),
+        ("--cov", Stat::Bivariate(|c| c.covariance(1)), None),
+        ("--corr", Stat::Bivariate(CoMoments::pearson), None),
+        ("--mode", Stat::Table(stats::mode_counts), None),
+        ("--freq", Stat::Table(stats::frequencies), None),
+        ("--histogram", Stat::Histogram, None),
+        ("regress", Stat::Regress, None),
+        (
+            "--l2",
+            Stat::Slice(|nums, nan| stats::try_l2(nums, Summation::default(), nan)),
+            None,
+        ),
     ];
-    let stat = argdescs
-        .iter()
-        .find(|(a, _)| a == target)
-        .unwrap_or_else(|| usage())
-        .1;
-
-    // Read the input.
-    use std::io::BufRead;
-    let nums: Vec<f64> = std::io::stdin()
-        .lock()
-        .lines()
-        .map(|s| {
-            let s = s.unwrap_or_else(|e| {
-                eprintln!("error reading input: {}", e);
-                exit(-1);
+    let paramdescs: &[(&str, ParamFn)] = &[
+        ("--power-mean", stats::power_mean),
+        ("--trimmed-mean", stats::trimmed_mean),
+        ("--winsorized-mean", stats::winsorized_mean),
+    ];
+    let cidescs: &[(&str, CiFn)] = &[
+        ("--mean", stats::mean_ci),
+        ("--median", stats::median_ci),
+        ("--variance", stats::variance_ci),
+        ("--sample-variance", stats::variance_ci),
+        ("--stddev", stddev_ci),
+        ("--population-stddev", stddev_ci),
+        ("--sample-stddev", stddev_ci),
+    ];
+    let mut nan = NanPolicy::default();
+    let mut weighted = None;
+    let mut tolerance = 0.0;
+    let mut bins = BinRule::default();
+    let mut level = None;
+    let mut alternative = Alternative::default();
+    let mut stat = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if let Some(policy) = arg.strip_prefix("--nan=") {
+            nan = policy.parse().unwrap_or_else(|e| {
+                eprintln!("stats: {}", e);
+                usage();
+            });
+            continue;
+        }
+        if arg == "--weighted" {
+            weighted = Some(WeightKind::default());
+            continue;
+        }
+        if let Some(kind) = arg.strip_prefix("--weighted=") {
+            weighted = Some(kind.parse().unwrap_or_else(|e| {
+                eprintln!("stats: {}", e);
+                usage();
+            }));
+            continue;
+        }
+        if let Some(width) = arg.strip_prefix("--tolerance=") {
+            tolerance = width.parse().unwrap_or_else(|e| {
+                eprintln!("stats: error parsing {}: {}", width, e);
+                usage();
+            });
+            continue;
+        }
+        if let Some(rule) = arg.strip_prefix("--bins=") {
+            bins = rule.parse().unwrap_or_else(|e| {
+                eprintln!("stats: {}", e);
+                usage();
             });
-            s.parse::<f64>().unwrap_or_else(|e| {
-                eprintln!("error parsing number {}: {}", s, e);
-                exit(-1);
+            continue;
+        }
+        if let Some(alt) = arg.strip_prefix("--alternative=") {
+            alternative = alt.parse().unwrap_or_else(|e| {
+                eprintln!("stats: {}", e);
+                usage();
+            });
+            continue;
+        }
+        if arg == "--ci" {
+            let param = args.next().unwrap_or_else(|| usage());
+            level = Some(param.parse().unwrap_or_else(|e| {
+                eprintln!("stats: error parsing {}: {}", param, e);
+                usage();
+            }));
+            continue;
+        }
+        if stat.is_some() {
+            usage();
+        }
+        stat = Some(match arg.as_str() {
+            "--quantile" | "--percentile" => {
+                let scale = if arg == "--percentile" { 100.0 } else { 1.0 };
+                let list = args.next().unwrap_or_else(|| usage());
+                (arg, Stat::Quantiles(parse_list(&list, scale)), None)
+            }
+            "ttest" | "mannwhitney" => {
+                let file_a = args.next().unwrap_or_else(|| usage());
+                let file_b = args.next().unwrap_or_else(|| usage());
+                if arg == "ttest" {
+                    (arg, Stat::TTest(file_a, file_b), None)
+                } else {
+                    (arg, Stat::MannWhitney(file_a, file_b), None)
+                }
+            }
+            _ => {
+                if let Some((_, f)) = paramdescs.iter().find(|(a, _)| *a == arg) {
+                    let param = args.next().unwrap_or_else(|| usage());
+                    let param = param.parse().unwrap_or_else(|e| {
+                        eprintln!("stats: error parsing {}: {}", param, e);
+                        usage();
+                    });
+                    (arg, Stat::Param(*f, param), None)
+                } else {
+                    let (_, stat, weighted) = argdescs
+                        .iter()
+                        .find(|(a, _, _)| *a == arg)
+                        .unwrap_or_else(|| usage());
+                    (arg, stat.clone(), *weighted)
+                }
+            }
+        });
+    }
+    let (target, stat, weighted_stat) = stat.unwrap_or_else(|| usage());
+    let ci = match level {
+        Some(_) if weighted.is_some() => {
+            eprintln!("stats: --ci does not support weights");
+            exit(1);
+        }
+        Some(_) if matches!(stat, Stat::Regress) => None,
+        Some(level) => {
+            let (_, f) = cidescs
+                .iter()
+                .find(|(a, _)| *a == target)
+                .unwrap_or_else(|| {
+                    eprintln!("stats: {} does not support --ci", target);
+                    exit(1);
+                });
+            Some((*f, level))
+        }
+        None => None,
+    };
+
+    // Read the input and run the stat, giving the lines of
+    // output.
+    let values = |xs: Vec<f64>| xs.iter().map(f64::to_string).collect::<Vec<String>>();
+    let result = match (weighted, stat, weighted_stat) {
+        (None, stat, _) if ci.is_some() => {
+            let (f, level) = ci.unwrap();
+            with_ci(stat, f, level, &read_nums().collect::<Vec<f64>>(), nan)
+        }
+        (None, Stat::Streaming(f), _) => {
+            let mut moments = Moments::with_nan_policy(nan);
+            moments.extend(read_nums());
+            f(&moments).map(|x| vec![x.to_string()])
+        }
+        (None, Stat::Bivariate(f), _) => {
+            let mut co_moments = CoMoments::with_nan_policy(nan);
+            co_moments.extend(read_pair_iter());
+            f(&co_moments).map(|x| vec![x.to_string()])
+        }
+        (None, Stat::Slice(f), _) => {
+            f(&read_nums().collect::<Vec<f64>>(), nan).map(|x| vec![x.to_string()])
+        }
+        (None, Stat::Param(f, param), _) => {
+            f(&read_nums().collect::<Vec<f64>>(), param, nan).map(|x| vec![x.to_string()])
+        }
+        (None, Stat::Quantiles(ps), _) => {
+            let nums: Vec<f64> = read_nums().collect();
+            stats::quantiles(&nums, &ps, QuantileMethod::default(), nan).map(values)
+        }
+        (None, Stat::Table(f), _) => {
+            let nums: Vec<f64> = read_nums().collect();
+            f(&nums, tolerance, nan).map(|table| {
+                table
+                    .iter()
+                    .map(|(x, count)| format!("{}\t{}", x, count))
+                    .collect()
             })
-        })
-        .collect();
+        }
+        (None, Stat::Histogram, _) => {
+            let nums: Vec<f64> = read_nums().collect();
+            Histogram::from_rule(&nums, bins, nan).map(|h| {
+                h.edges()
+                    .windows(2)
+                    .zip(h.counts())
+                    .map(|(w, count)| format!("{}\t{}\t{}", w[0], w[1], count))
+                    .collect()
+            })
+        }
+        (None, Stat::Regress, _) => {
+            let level = level.unwrap_or(0.95);
+            let (xs, ys) = read_pairs();
+            stats::linear_regression(&xs, &ys, nan).and_then(|fit| {
+                let row = |name, estimate, se, ci: Interval| {
+                    format!("{}\t{}\t{}\t{}\t{}", name, estimate, se, ci.lower, ci.upper)
+                };
+                Ok(vec![
+                    row("slope", fit.slope, fit.slope_se, fit.slope_ci(level)?),
+                    row(
+                        "intercept",
+                        fit.intercept,
+                        fit.intercept_se,
+                        fit.intercept_ci(level)?,
+                    ),
+                    format!("r-squared\t{}", fit.r_squared),
+                    format!("residual-se\t{}", fit.residual_se),
+                ])
+            })
+        }
+        (None, Stat::TTest(file_a, file_b), _) => {
+            let xs = read_file_nums(&file_a);
+            let ys = read_file_nums(&file_b);
+            stats::welch_t_test(&xs, &ys, alternative, nan).map(|t| {
+                vec![
+                    format!("t\t{}", t.statistic),
+                    format!("df\t{}", t.df),
+                    format!("p-value\t{}", t.p_value),
+                    format!("effect-size\t{}", t.effect_size),
+                ]
+            })
+        }
+        (None, Stat::MannWhitney(file_a, file_b), _) => {
+            let xs = read_file_nums(&file_a);
+            let ys = read_file_nums(&file_b);
+            stats::mann_whitney_u(&xs, &ys, alternative, nan).map(|u| {
+                vec![
+                    format!("U\t{}", u.statistic),
+                    format!("p-value\t{}", u.p_value),
+                ]
+            })
+        }
+        (Some(_), Stat::Quantiles(ps), _) => {
+            let (nums, weights) = read_pairs();
+            stats::weighted_quantiles(&nums, &weights, &ps, nan).map(values)
+        }
+        (Some(kind), _, Some(f)) => {
+            let (nums, weights) = read_pairs();
+            f(&nums, &weights, kind, nan).map(|x| vec![x.to_string()])
+        }
+        (Some(_), _, None) => {
+            eprintln!("stats: {} does not support weights", target);
+            exit(1);
+        }
+    };
 
-    // Run the stat, show the result if any.
-    if let Some(result) = stat(&nums) {
-        println!("{}", result);
+    // Show the results, or why there are none.
+    match result {
+        Ok(results) => {
+            for result in results {
+                println!("{}", result);
+            }
+        }
+        Err(e) => {
+            eprintln!("stats: {}", e);
+            exit(1);
+        }
     }
 }
@@ -0,0 +1,222 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Means other than the arithmetic mean.
+
+use crate::error::*;
+use crate::nan::*;
+use crate::num::*;
+use crate::order::*;
+use crate::sum::*;
+
+/// Check that NaN-free values are positive and there is at
+/// least one of them.
+fn check_positive(nums: &[f64]) -> StatResult<()> {
+    if nums.is_empty() {
+        Err(StatError::Empty)
+    } else if nums.iter().any(|&x| x <= 0.0) {
+        Err(StatError::InvalidParameter("values must be positive"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Geometric mean of input values: the `n`-th root of
+/// their product. Suits growth rates and ratios. The values
+/// must be positive.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let g = geometric_mean(&[1.0, 3.0, 9.0], NanPolicy::Error).unwrap();
+/// assert!((g - 3.0).abs() < 1e-15);
+/// ```
+/// ```
+/// # use stats::*;
+/// let g = geometric_mean(&[1e300, 1e300], NanPolicy::Error).unwrap();
+/// assert!((g / 1e300 - 1.0).abs() < 1e-12);
+/// ```
+/// ```
+/// # use stats::*;
+/// let err = StatError::InvalidParameter("values must be positive");
+/// assert_eq!(Err(err), geometric_mean(&[1.0, 0.0], NanPolicy::Error));
+/// ```
+pub fn geometric_mean<T: Numeric>(nums: &[T], nan: NanPolicy) -> StatResult {
+    nan.stat(nums, |nums| {
+        check_positive(nums)?;
+        let logs = Summation::default().sum_iter(nums.iter().map(|x| x.ln()));
+        finite((logs / nums.len() as f64).exp())
+    })
+}
+
+/// Harmonic mean of input values: the reciprocal of the
+/// mean of their reciprocals. Suits rates, such as the
+/// average throughput over equal amounts of work. The
+/// values must be positive.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(2.0), harmonic_mean(&[1.0, 4.0, 4.0], NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert!(harmonic_mean(&[1.0, -4.0], NanPolicy::Error).is_err());
+/// ```
+pub fn harmonic_mean<T: Numeric>(nums: &[T], nan: NanPolicy) -> StatResult {
+    nan.stat(nums, |nums| {
+        check_positive(nums)?;
+        let recips = Summation::default().sum_iter(nums.iter().map(|x| x.recip()));
+        finite(nums.len() as f64 / recips)
+    })
+}
+
+/// Power mean (generalized or Hölder mean) of input values
+/// with exponent `p`: the `p`-th root of the mean of their
+/// `p`-th powers. An exponent of 1 gives the arithmetic
+/// mean, 0 the [geometric mean](geometric_mean), -1 the
+/// [harmonic mean](harmonic_mean), and infinite exponents
+/// the largest and smallest values. The values must be
+/// positive.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let m = power_mean(&[1.0, 7.0], 2.0, NanPolicy::Error).unwrap();
+/// assert!((m - 5.0).abs() < 1e-15);
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, 4.0, 4.0];
+/// assert_eq!(harmonic_mean(&nums, NanPolicy::Error), power_mean(&nums, -1.0, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let m = power_mean(&[1.0, 7.0], std::f64::NEG_INFINITY, NanPolicy::Error);
+/// assert_eq!(Ok(1.0), m);
+/// ```
+/// ```
+/// # use stats::*;
+/// let m = power_mean(&[1e300, 1e300], 3.0, NanPolicy::Error);
+/// assert_eq!(Ok(1e300), m);
+/// ```
+/// ```
+/// # use stats::*;
+/// let m = power_mean(&[1e-3, 1.0], -1000.0, NanPolicy::Error).unwrap();
+/// assert!((m / (1e-3 * 2f64.powf(1e-3)) - 1.0).abs() < 1e-15);
+/// ```
+pub fn power_mean<T: Numeric>(nums: &[T], p: f64, nan: NanPolicy) -> StatResult {
+    if p.is_nan() {
+        return Err(StatError::InvalidParameter("exponent must not be NaN"));
+    }
+    if p == 0.0 {
+        return geometric_mean(nums, nan);
+    }
+    if p == -1.0 {
+        return harmonic_mean(nums, nan);
+    }
+    nan.stat(nums, |nums| {
+        check_positive(nums)?;
+        let max = nums.iter().cloned().fold(0.0, f64::max);
+        let min = nums.iter().cloned().fold(f64::INFINITY, f64::min);
+        if p == f64::INFINITY {
+            return Ok(max);
+        }
+        if p == f64::NEG_INFINITY {
+            return Ok(min);
+        }
+        // Scaling by the largest value for a positive exponent,
+        // or the smallest for a negative one, makes every power
+        // at most 1 and one of them exactly 1, so their sum can
+        // neither overflow nor vanish.
+        let scale = if p > 0.0 { max } else { min };
+        let powers = Summation::default().sum_iter(nums.iter().map(|x| (x / scale).powf(p)));
+        finite(scale * (powers / nums.len() as f64).powf(p.recip()))
+    })
+}
+
+/// Check that a trimming fraction is in `[0, 0.5)`.
+fn check_fraction(fraction: f64) -> StatResult<()> {
+    if (0.0..0.5).contains(&fraction) {
+        Ok(())
+    } else {
+        Err(StatError::InvalidParameter("fraction must be in [0, 0.5)"))
+    }
+}
+
+/// The NaN-free, nonempty `nums` in increasing order, with
+/// the number to cut from each end for `fraction`.
+fn sorted_cut(nums: &[f64], fraction: f64) -> StatResult<(Vec<f64>, usize)> {
+    if nums.is_empty() {
+        return Err(StatError::Empty);
+    }
+    let mut nums = nums.to_owned();
+    nums.sort_unstable_by(cmp_f64);
+    let cut = (fraction * nums.len() as f64) as usize;
+    Ok((nums, cut))
+}
+
+/// Trimmed mean of input values: the arithmetic mean after
+/// removing the fraction `fraction` of the values, rounded
+/// down, from each end. The fraction must be at least 0 and
+/// less than one half.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, 2.0, 3.0, 4.0, 100.0];
+/// assert_eq!(Ok(3.0), trimmed_mean(&nums, 0.2, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, 2.0, 3.0, 4.0, 100.0];
+/// assert_eq!(Ok(22.0), trimmed_mean(&nums, 0.1, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert!(trimmed_mean(&[1.0, 2.0], 0.5, NanPolicy::Error).is_err());
+/// ```
+pub fn trimmed_mean<T: Numeric>(nums: &[T], fraction: f64, nan: NanPolicy) -> StatResult {
+    check_fraction(fraction)?;
+    nan.stat(nums, |nums| {
+        let (nums, cut) = sorted_cut(nums, fraction)?;
+        let kept = &nums[cut..nums.len() - cut];
+        finite(sum(kept, Summation::default()) / kept.len() as f64)
+    })
+}
+
+/// Winsorized mean of input values: the arithmetic mean
+/// after replacing the fraction `fraction` of the values,
+/// rounded down, at each end with the nearest remaining
+/// value. The fraction must be at least 0 and less than
+/// one half.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, 2.0, 3.0, 4.0, 100.0];
+/// assert_eq!(Ok(3.0), winsorized_mean(&nums, 0.2, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [0.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 100.0];
+/// assert_eq!(Ok(5.5), winsorized_mean(&nums, 0.1, NanPolicy::Error));
+/// ```
+pub fn winsorized_mean<T: Numeric>(nums: &[T], fraction: f64, nan: NanPolicy) -> StatResult {
+    check_fraction(fraction)?;
+    nan.stat(nums, |nums| {
+        let (mut nums, cut) = sorted_cut(nums, fraction)?;
+        let n = nums.len();
+        let (lo, hi) = (nums[cut], nums[n - 1 - cut]);
+        nums[..cut].iter_mut().for_each(|x| *x = lo);
+        nums[n - cut..].iter_mut().for_each(|x| *x = hi);
+        finite(sum(&nums, Summation::default()) / n as f64)
+    })
+}
@@ -0,0 +1,458 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Streaming accumulation of count, mean, variance,
+//! higher moments and range.
+
+use crate::error::*;
+use crate::nan::*;
+use crate::shape::*;
+
+/// Online accumulator for the mean, variance, skewness,
+/// kurtosis and range of a stream of numbers. Uses
+/// Welford's single-pass update, extended to the third and
+/// fourth moments by Pébay, which is numerically stable and
+/// needs only constant memory. Accumulators built over
+/// separate parts of the input can be combined exactly with
+/// [`merge`](Moments::merge).
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let mut m = Moments::new();
+/// for &x in &[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+///     m.push(x);
+/// }
+/// assert_eq!(8, m.count());
+/// assert_eq!(Ok(5.0), m.mean());
+/// assert_eq!(Ok(4.0), m.variance(0));
+/// assert_eq!(Ok(2.0), m.stddev(0));
+/// ```
+/// ```
+/// # use stats::*;
+/// let m: Moments = [1.0, 1.0, -5.0].iter().collect();
+/// assert_eq!(Ok(-1.0), m.mean());
+/// assert_eq!(Ok(12.0), m.variance(1));
+/// assert_eq!(Ok(-5.0), m.min());
+/// assert_eq!(Ok(1.0), m.max());
+/// ```
+/// ```
+/// # use stats::*;
+/// let m: Moments = [3.0].iter().collect();
+/// assert_eq!(Ok(0.0), m.variance(0));
+/// assert_eq!(
+///     Err(StatError::TooFewSamples { needed: 2, got: 1 }),
+///     m.variance(1),
+/// );
+/// ```
+/// ```
+/// # use stats::*;
+/// let m: Moments = [3.0, std::f64::NAN].iter().collect();
+/// assert_eq!(1, m.nans());
+/// assert_eq!(Err(StatError::NanInput), m.mean());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Moments {
+    count: u64,
+    mean: f64,
+    m2: f64,
+    m3: f64,
+    m4: f64,
+    min: f64,
+    max: f64,
+    nans: u64,
+    nan: NanPolicy,
+}
+
+impl Default for Moments {
+    fn default() -> Self {
+        Moments {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            m3: 0.0,
+            m4: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            nans: 0,
+            nan: NanPolicy::default(),
+        }
+    }
+}
+
+impl Moments {
+    /// Make a new empty accumulator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make a new empty accumulator that treats NaNs
+    /// according to `nan`.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use stats::*;
+    /// let mut m = Moments::with_nan_policy(NanPolicy::Skip);
+    /// m.extend(&[1.0, std::f64::NAN, 3.0]);
+    /// assert_eq!(Ok(2.0), m.mean());
+    /// ```
+    /// ```
+    /// # use stats::*;
+    /// let mut m = Moments::with_nan_policy(NanPolicy::Propagate);
+    /// m.extend(&[1.0, std::f64::NAN, 3.0]);
+    /// assert!(m.mean().unwrap().is_nan());
+    /// ```
+    pub fn with_nan_policy(nan: NanPolicy) -> Self {
+        Moments {
+            nan,
+            ..Self::default()
+        }
+    }
+
+    /// Rebuild an accumulator from its [parts](Moments::parts).
+    /// This allows partial results to be shipped between
+    /// processes and then [merged](Moments::merge).
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use stats::*;
+    /// let m: Moments = [2.0, -1.0, 4.0].iter().collect();
+    /// let copy = Moments::from_parts(m.parts());
+    /// assert_eq!(m, copy);
+    /// ```
+    pub fn from_parts(parts: MomentParts) -> Self {
+        if parts.count == 0 {
+            return Self::default();
+        }
+        Moments {
+            count: parts.count,
+            mean: parts.mean,
+            m2: parts.sum_sq_dev,
+            m3: parts.sum_cube_dev,
+            m4: parts.sum_fourth_dev,
+            min: parts.min,
+            max: parts.max,
+            ..Self::default()
+        }
+    }
+
+    /// The accumulated state, not counting NaNs, from which
+    /// the accumulator can be rebuilt with
+    /// [`from_parts`](Moments::from_parts).
+    pub fn parts(&self) -> MomentParts {
+        MomentParts {
+            count: self.count,
+            mean: self.mean,
+            sum_sq_dev: self.m2,
+            sum_cube_dev: self.m3,
+            sum_fourth_dev: self.m4,
+            min: self.min,
+            max: self.max,
+        }
+    }
+
+    /// Add a value to the accumulator.
+    pub fn push(&mut self, x: f64) {
+        if x.is_nan() {
+            self.nans += 1;
+            return;
+        }
+        self.count += 1;
+        let n = self.count as f64;
+        let delta = x - self.mean;
+        let delta_n = delta / n;
+        let term = delta * delta_n * (n - 1.0);
+        self.mean += delta_n;
+        self.m4 += term * delta_n * delta_n * (n * n - 3.0 * n + 3.0)
+            + 6.0 * delta_n * delta_n * self.m2
+            - 4.0 * delta_n * self.m3;
+        self.m3 += term * delta_n * (n - 2.0) - 3.0 * delta_n * self.m2;
+        self.m2 += delta * (x - self.mean);
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+    }
+
+    /// Combine the values accumulated by `other` into this
+    /// accumulator, as if they had been pushed here. Uses
+    /// the pairwise update of Chan, Golub and LeVeque,
+    /// extended to higher moments by Pébay.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use stats::*;
+    /// let mut left: Moments = [1.0, 1.0].iter().collect();
+    /// let right: Moments = [-5.0, -10.0].iter().collect();
+    /// left.merge(&right);
+    /// assert_eq!(4, left.count());
+    /// assert_eq!(Ok(-3.25), left.mean());
+    /// assert_eq!(Ok(28.25), left.variance(1));
+    /// assert_eq!(Ok(-10.0), left.min());
+    /// assert_eq!(Ok(1.0), left.max());
+    /// ```
+    /// ```
+    /// # use stats::*;
+    /// let mut m = Moments::new();
+    /// let right: Moments = [3.0, 4.0].iter().collect();
+    /// m.merge(&right);
+    /// m.merge(&Moments::new());
+    /// assert_eq!(right, m);
+    /// ```
+    /// ```
+    /// # use stats::*;
+    /// let nums = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+    /// let mut m: Moments = nums[..3].iter().collect();
+    /// m.merge(&nums[3..].iter().collect());
+    /// let g1 = m.skewness(Bias::Biased).unwrap();
+    /// assert!((g1 - 0.65625).abs() < 1e-15);
+    /// ```
+    pub fn merge(&mut self, other: &Self) {
+        self.nans += other.nans;
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = Moments {
+                nans: self.nans,
+                nan: self.nan,
+                ..*other
+            };
+            return;
+        }
+        let n1 = self.count as f64;
+        let n2 = other.count as f64;
+        let count = self.count + other.count;
+        let n = count as f64;
+        let delta = other.mean - self.mean;
+        let delta2 = delta * delta;
+        self.mean += delta * (n2 / n);
+        self.m4 += other.m4
+            + delta2 * delta2 * (n1 * n2 * (n1 * n1 - n1 * n2 + n2 * n2) / (n * n * n))
+            + 6.0 * delta2 * (n1 * n1 * other.m2 + n2 * n2 * self.m2) / (n * n)
+            + 4.0 * delta * (n1 * other.m3 - n2 * self.m3) / n;
+        self.m3 += other.m3
+            + delta2 * delta * (n1 * n2 * (n1 - n2) / (n * n))
+            + 3.0 * delta * (n1 * other.m2 - n2 * self.m2) / n;
+        self.m2 += other.m2 + delta2 * (n1 * n2 / n);
+        self.count = count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    /// Number of values accumulated so far.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Number of NaNs pushed so far. NaNs are not
+    /// accumulated: they are handled according to the
+    /// accumulator's [`NanPolicy`] when a statistic is
+    /// requested.
+    pub fn nans(&self) -> u64 {
+        self.nans
+    }
+
+    /// Apply the NaN policy, then compute a statistic with
+    /// `f` if NaNs are not propagated.
+    fn checked_with<F>(&self, f: F) -> StatResult
+    where
+        F: FnOnce() -> StatResult,
+    {
+        if self.nans > 0 {
+            match self.nan {
+                NanPolicy::Propagate => return Ok(f64::NAN),
+                NanPolicy::Error => return Err(StatError::NanInput),
+                NanPolicy::Skip => (),
+            }
+        }
+        f()
+    }
+
+    /// Apply the NaN policy, check that at least `needed`
+    /// values have been accumulated, then return `value` if
+    /// it is finite.
+    fn checked(&self, needed: u64, value: f64) -> StatResult {
+        self.checked_with(|| {
+            if self.count == 0 {
+                Err(StatError::Empty)
+            } else if self.count < needed {
+                Err(StatError::TooFewSamples {
+                    needed: needed as usize,
+                    got: self.count as usize,
+                })
+            } else {
+                finite(value)
+            }
+        })
+    }
+
+    /// Arithmetic mean of the values accumulated so far.
+    /// The mean of no values is undefined.
+    pub fn mean(&self) -> StatResult {
+        self.checked(1, self.mean)
+    }
+
+    /// Sum of squared deviations from the mean of the
+    /// values accumulated so far. The sum is 0.0 for no
+    /// values.
+    pub fn sum_sq_dev(&self) -> f64 {
+        self.m2
+    }
+
+    /// Smallest value accumulated so far.
+    pub fn min(&self) -> StatResult {
+        self.checked(1, self.min)
+    }
+
+    /// Largest value accumulated so far.
+    pub fn max(&self) -> StatResult {
+        self.checked(1, self.max)
+    }
+
+    /// Variance of the values accumulated so far, with
+    /// `ddof` delta degrees of freedom: the sum of squared
+    /// deviations is divided by `n - ddof`. Use 0 for the
+    /// population variance and 1 for the sample variance.
+    /// More than `ddof` values are needed.
+    pub fn variance(&self, ddof: usize) -> StatResult {
+        let dof = self.count as f64 - ddof as f64;
+        self.checked(ddof as u64 + 1, self.m2 / dof)
+    }
+
+    /// Standard deviation of the values accumulated so far,
+    /// with `ddof` delta degrees of freedom as for
+    /// [`variance`](Moments::variance).
+    pub fn stddev(&self, ddof: usize) -> StatResult {
+        self.variance(ddof).map(f64::sqrt)
+    }
+
+    /// The `k`-th central moment of the values accumulated
+    /// so far, as for [`central_moment`](crate::central_moment).
+    /// Only moments up to the fourth are accumulated.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use stats::*;
+    /// let m: Moments = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0].iter().collect();
+    /// assert_eq!(Ok(4.0), m.central_moment(2));
+    /// ```
+    /// ```
+    /// # use stats::*;
+    /// let m: Moments = [1.0, 2.0].iter().collect();
+    /// assert!(m.central_moment(5).is_err());
+    /// ```
+    pub fn central_moment(&self, k: u32) -> StatResult {
+        let sum = match k {
+            0 => self.count as f64,
+            1 => 0.0,
+            2 => self.m2,
+            3 => self.m3,
+            4 => self.m4,
+            _ => {
+                return Err(StatError::InvalidParameter(
+                    "streaming central moment must be of order at most 4",
+                ))
+            }
+        };
+        self.checked(1, sum / self.count as f64)
+    }
+
+    /// Skewness of the values accumulated so far, as for
+    /// [`skewness`](crate::skewness).
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use stats::*;
+    /// let nums = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+    /// let m: Moments = nums.iter().collect();
+    /// let g1 = m.skewness(Bias::Corrected).unwrap();
+    /// let expected = skewness(&nums, Bias::Corrected, NanPolicy::Error).unwrap();
+    /// assert!((g1 - expected).abs() < 1e-15);
+    /// ```
+    pub fn skewness(&self, bias: Bias) -> StatResult {
+        let n = self.count as f64;
+        self.checked_with(|| skewness_from(n, self.m2, self.m3, bias))
+    }
+
+    /// Kurtosis of the values accumulated so far, as for
+    /// [`kurtosis`](crate::kurtosis).
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use stats::*;
+    /// let m: Moments = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0].iter().collect();
+    /// assert_eq!(Ok(2.78125), m.kurtosis(Bias::Biased));
+    /// ```
+    /// ```
+    /// # use stats::*;
+    /// let m: Moments = [5.0; 4].iter().collect();
+    /// assert_eq!(Err(StatError::ZeroVariance), m.kurtosis(Bias::Corrected));
+    /// ```
+    pub fn kurtosis(&self, bias: Bias) -> StatResult {
+        let n = self.count as f64;
+        self.checked_with(|| kurtosis_from(n, self.m2, self.m4, bias))
+    }
+
+    /// Excess kurtosis of the values accumulated so far, as
+    /// for [`excess_kurtosis`](crate::excess_kurtosis).
+    pub fn excess_kurtosis(&self, bias: Bias) -> StatResult {
+        self.kurtosis(bias).map(|b2| b2 - 3.0)
+    }
+}
+
+/// The accumulated state of a [`Moments`], for shipping
+/// partial results between processes. Sums of powers of
+/// deviations are taken from the mean.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MomentParts {
+    /// Number of values.
+    pub count: u64,
+    /// Arithmetic mean.
+    pub mean: f64,
+    /// Sum of squared deviations.
+    pub sum_sq_dev: f64,
+    /// Sum of cubed deviations.
+    pub sum_cube_dev: f64,
+    /// Sum of fourth powers of deviations.
+    pub sum_fourth_dev: f64,
+    /// Smallest value.
+    pub min: f64,
+    /// Largest value.
+    pub max: f64,
+}
+
+impl Extend<f64> for Moments {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl<'a> Extend<&'a f64> for Moments {
+    fn extend<I: IntoIterator<Item = &'a f64>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
+impl std::iter::FromIterator<f64> for Moments {
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        let mut m = Moments::new();
+        m.extend(iter);
+        m
+    }
+}
+
+impl<'a> std::iter::FromIterator<&'a f64> for Moments {
+    fn from_iter<I: IntoIterator<Item = &'a f64>>(iter: I) -> Self {
+        iter.into_iter().cloned().collect()
+    }
+}
@@ -0,0 +1,257 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Handling of NaNs in the input.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::*;
+use crate::num::*;
+
+/// The NaN-free values of a slice, as `f64`.
+pub(crate) type Values<'a, T> =
+    std::iter::Filter<std::iter::Map<std::slice::Iter<'a, T>, fn(&T) -> f64>, fn(&f64) -> bool>;
+
+/// What a statistic should do when its input contains a
+/// NaN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NanPolicy {
+    /// The statistic is NaN.
+    Propagate,
+    /// NaNs are omitted from the input.
+    Skip,
+    /// The statistic fails with [`StatError::NanInput`].
+    /// This is the default.
+    #[default]
+    Error,
+}
+
+impl NanPolicy {
+    /// Apply the policy to `nums` of any numeric type,
+    /// giving the values to compute with, or `None` if the
+    /// statistic is NaN.
+    pub(crate) fn apply<T: Numeric>(self, nums: &[T]) -> StatResult<Option<Cow<'_, [f64]>>> {
+        if !nums.iter().any(|x| x.is_nan()) {
+            return Ok(Some(to_f64s(nums)));
+        }
+        match self {
+            NanPolicy::Propagate => Ok(None),
+            NanPolicy::Skip => {
+                let nums = nums
+                    .iter()
+                    .filter(|x| !x.is_nan())
+                    .map(|x| x.to_f64())
+                    .collect();
+                Ok(Some(Cow::Owned(nums)))
+            }
+            NanPolicy::Error => Err(StatError::NanInput),
+        }
+    }
+
+    /// Apply the policy to the paired values `xs` and `ys`,
+    /// of any numeric types, which must have the same
+    /// length, giving the pairs to compute with, or `None`
+    /// if the statistic is NaN. A pair is skipped if either
+    /// of its values is NaN.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn apply2<'a, T: Numeric, U: Numeric>(
+        self,
+        xs: &'a [T],
+        ys: &'a [U],
+    ) -> StatResult<Option<(Cow<'a, [f64]>, Cow<'a, [f64]>)>> {
+        if xs.len() != ys.len() {
+            return Err(StatError::LengthMismatch {
+                left: xs.len(),
+                right: ys.len(),
+            });
+        }
+        let is_nan = |(x, y): (&T, &U)| x.is_nan() || y.is_nan();
+        if !xs.iter().zip(ys).any(is_nan) {
+            return Ok(Some((to_f64s(xs), to_f64s(ys))));
+        }
+        match self {
+            NanPolicy::Propagate => Ok(None),
+            NanPolicy::Skip => {
+                let (xs, ys) = xs
+                    .iter()
+                    .zip(ys)
+                    .filter(|&p| !is_nan(p))
+                    .map(|(x, y)| (x.to_f64(), y.to_f64()))
+                    .unzip();
+                Ok(Some((Cow::Owned(xs), Cow::Owned(ys))))
+            }
+            NanPolicy::Error => Err(StatError::NanInput),
+        }
+    }
+
+    /// Apply the policy to `nums` in place, giving the
+    /// values to compute with, or `None` if the statistic is
+    /// NaN. Skipped NaNs are moved to the end of `nums`.
+    pub(crate) fn apply_mut(self, nums: &mut [f64]) -> StatResult<Option<&mut [f64]>> {
+        if !nums.iter().any(|x| x.is_nan()) {
+            return Ok(Some(nums));
+        }
+        match self {
+            NanPolicy::Propagate => Ok(None),
+            NanPolicy::Skip => {
+                let mut n = 0;
+                for i in 0..nums.len() {
+                    if !nums[i].is_nan() {
+                        nums.swap(n, i);
+                        n += 1;
+                    }
+                }
+                Ok(Some(&mut nums[..n]))
+            }
+            NanPolicy::Error => Err(StatError::NanInput),
+        }
+    }
+
+    /// Apply the policy to `nums` of any numeric type,
+    /// giving an iterator over the values to compute with,
+    /// or `None` if the statistic is NaN. Nothing is
+    /// copied.
+    pub(crate) fn apply_values<T: Numeric>(self, nums: &[T]) -> StatResult<Option<Values<'_, T>>> {
+        if nums.iter().any(|x| x.is_nan()) {
+            match self {
+                NanPolicy::Propagate => return Ok(None),
+                NanPolicy::Error => return Err(StatError::NanInput),
+                NanPolicy::Skip => (),
+            }
+        }
+        let to_f64: fn(&T) -> f64 = |x| x.to_f64();
+        let not_nan: fn(&f64) -> bool = |x| !x.is_nan();
+        Ok(Some(nums.iter().map(to_f64).filter(not_nan)))
+    }
+
+    /// Compute the statistic `f` on the values of `nums`,
+    /// of any numeric type, under this policy. `f` will
+    /// never see a NaN.
+    pub(crate) fn stat_values<T, F>(self, nums: &[T], f: F) -> StatResult
+    where
+        T: Numeric,
+        F: FnOnce(Values<'_, T>) -> StatResult,
+    {
+        match self.apply_values(nums)? {
+            Some(values) => f(values),
+            None => Ok(f64::NAN),
+        }
+    }
+
+    /// Compute the statistic `f` on `nums`, of any numeric
+    /// type, under this policy. `f` will never see a NaN.
+    pub(crate) fn stat<T, F>(self, nums: &[T], f: F) -> StatResult
+    where
+        T: Numeric,
+        F: FnOnce(&[f64]) -> StatResult,
+    {
+        self.stat_or(nums, f64::NAN, f)
+    }
+
+    /// Compute the statistic `f` on `nums` under this
+    /// policy, giving `propagated` if the statistic is NaN.
+    pub(crate) fn stat_or<T, R, F>(self, nums: &[T], propagated: R, f: F) -> StatResult<R>
+    where
+        T: Numeric,
+        F: FnOnce(&[f64]) -> StatResult<R>,
+    {
+        match self.apply(nums)? {
+            Some(nums) => f(&nums),
+            None => Ok(propagated),
+        }
+    }
+
+    /// Compute the statistic `f` on the paired values `xs`
+    /// and `ys` under this policy. `f` will never see a NaN.
+    pub(crate) fn stat2<T, U, F>(self, xs: &[T], ys: &[U], f: F) -> StatResult
+    where
+        T: Numeric,
+        U: Numeric,
+        F: FnOnce(&[f64], &[f64]) -> StatResult,
+    {
+        self.stat2_or(xs, ys, f64::NAN, f)
+    }
+
+    /// Compute the statistic `f` on the paired values `xs`
+    /// and `ys` under this policy, giving `propagated` if
+    /// the statistic is NaN.
+    pub(crate) fn stat2_or<T, U, R, F>(
+        self,
+        xs: &[T],
+        ys: &[U],
+        propagated: R,
+        f: F,
+    ) -> StatResult<R>
+    where
+        T: Numeric,
+        U: Numeric,
+        F: FnOnce(&[f64], &[f64]) -> StatResult<R>,
+    {
+        match self.apply2(xs, ys)? {
+            Some((xs, ys)) => f(&xs, &ys),
+            None => Ok(propagated),
+        }
+    }
+
+    /// Compute the statistic `f` on the independent samples
+    /// `xs` and `ys`, which may differ in length, under this
+    /// policy, giving `propagated` if the statistic is NaN.
+    /// The policy is applied to each sample separately.
+    pub(crate) fn stat_samples_or<T, U, R, F>(
+        self,
+        xs: &[T],
+        ys: &[U],
+        propagated: R,
+        f: F,
+    ) -> StatResult<R>
+    where
+        T: Numeric,
+        U: Numeric,
+        F: FnOnce(&[f64], &[f64]) -> StatResult<R>,
+    {
+        match (self.apply(xs)?, self.apply(ys)?) {
+            (Some(xs), Some(ys)) => f(&xs, &ys),
+            _ => Ok(propagated),
+        }
+    }
+}
+
+impl fmt::Display for NanPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NanPolicy::Propagate => write!(f, "propagate"),
+            NanPolicy::Skip => write!(f, "skip"),
+            NanPolicy::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Parse a NaN policy from its name: `propagate`, `skip`
+/// (or `omit`) or `error`.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(NanPolicy::Skip), "skip".parse());
+/// ```
+/// ```
+/// # use stats::*;
+/// assert!("ignore".parse::<NanPolicy>().is_err());
+/// ```
+impl FromStr for NanPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "propagate" => Ok(NanPolicy::Propagate),
+            "skip" | "omit" => Ok(NanPolicy::Skip),
+            "error" => Ok(NanPolicy::Error),
+            _ => Err(format!("unknown NaN policy {}", s)),
+        }
+    }
+}
@@ -0,0 +1,296 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Vector norms and the distances between vectors they
+//! induce.
+
+use crate::error::*;
+use crate::nan::*;
+use crate::num::*;
+use crate::sum::*;
+
+/// Check that `p` gives a norm.
+fn check_norm_p(p: f64) -> StatResult<()> {
+    if p >= 1.0 {
+        Ok(())
+    } else {
+        Err(StatError::InvalidParameter("p must be at least 1"))
+    }
+}
+
+/// The `p`-norm of the NaN-free `values`.
+fn norm<I>(values: I, p: f64, summation: Summation) -> StatResult
+where
+    I: Iterator<Item = f64> + Clone,
+{
+    let max = values.clone().map(f64::abs).fold(0.0, f64::max);
+    if p == f64::INFINITY || max == 0.0 || max == f64::INFINITY {
+        return finite(max);
+    }
+    if p == 1.0 {
+        return finite(summation.sum_iter(values.map(f64::abs)));
+    }
+    // Scaling by the largest magnitude makes every power at
+    // most 1 and one of them exactly 1, so their sum can
+    // neither overflow nor vanish.
+    let scaled = values.map(|x| x.abs() / max);
+    let total = if p == 2.0 {
+        summation.sum_iter(scaled.map(|x| x * x)).sqrt()
+    } else {
+        summation.sum_iter(scaled.map(|x| x.powf(p))).powf(1.0 / p)
+    };
+    finite(max * total)
+}
+
+/// Lp norm of input values, for `p` at least 1: the `p`-th
+/// root of the sum of the `p`-th powers of their absolute
+/// values. An infinite `p` gives the [maximum norm](linf).
+/// The Lp norm of an empty list is 0.0.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(Some(5.0), lp(&[-3.0, 4.0], 2.0));
+/// ```
+/// ```
+/// # use stats::*;
+/// let norm = lp(&[3.0, 4.0, 5.0], 3.0).unwrap();
+/// assert!((norm - 6.0).abs() < 1e-14);
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Some(0.0), lp::<f64>(&[], 4.0));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(None, lp(&[1.0, 2.0], 0.5));
+/// ```
+pub fn lp<T: Numeric>(nums: &[T], p: f64) -> Option<f64> {
+    try_lp(nums, p, Summation::default(), NanPolicy::default()).ok()
+}
+
+/// Lp norm of input values, as for [`lp`], adding up the
+/// powers with the given strategy.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [3.0, -4.0];
+/// assert_eq!(Ok(4.0), try_lp(&nums, std::f64::INFINITY, Summation::Neumaier, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let norm = try_lp(&[1e200, 1e200], 2.0, Summation::Neumaier, NanPolicy::Error).unwrap();
+/// assert!((norm - 2f64.sqrt() * 1e200).abs() < 1e185);
+/// let norm = try_lp(&[1e200, -1e200], 3.0, Summation::Neumaier, NanPolicy::Error).unwrap();
+/// assert!((norm - 2f64.cbrt() * 1e200).abs() < 1e185);
+/// assert_eq!(Ok(2.0), try_lp(&[2.0, 1.0], 2000.0, Summation::Neumaier, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [3.0, -4.0];
+/// let err = StatError::InvalidParameter("p must be at least 1");
+/// assert_eq!(Err(err), try_lp(&nums, std::f64::NAN, Summation::Neumaier, NanPolicy::Error));
+/// ```
+pub fn try_lp<T: Numeric>(nums: &[T], p: f64, summation: Summation, nan: NanPolicy) -> StatResult {
+    check_norm_p(p)?;
+    nan.stat_values(nums, |values| norm(values, p, summation))
+}
+
+/// L1 norm (Manhattan norm) of input values: the sum of
+/// their absolute values. The L1 norm of an empty list is
+/// 0.0.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(Some(7.0), l1(&[-3.0, 4.0]));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Some(6.0), l1(&[-1, 2, -3]));
+/// ```
+pub fn l1<T: Numeric>(nums: &[T]) -> Option<f64> {
+    try_l1(nums, Summation::default(), NanPolicy::default()).ok()
+}
+
+/// L1 norm (Manhattan norm) of input values, as for
+/// [`l1`], adding up the absolute values with the given
+/// strategy.
+pub fn try_l1<T: Numeric>(nums: &[T], summation: Summation, nan: NanPolicy) -> StatResult {
+    try_lp(nums, 1.0, summation, nan)
+}
+
+/// L∞ norm (maximum norm) of input values: the largest of
+/// their absolute values. The L∞ norm of an empty list is
+/// 0.0.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(Some(4.0), linf(&[-3.0, -4.0, 1.0]));
+/// ```
+pub fn linf<T: Numeric>(nums: &[T]) -> Option<f64> {
+    try_linf(nums, NanPolicy::default()).ok()
+}
+
+/// L∞ norm (maximum norm) of input values, as for
+/// [`linf`].
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [-3.0, std::f64::NAN, 2.0];
+/// assert_eq!(Ok(3.0), try_linf(&nums, NanPolicy::Skip));
+/// ```
+pub fn try_linf<T: Numeric>(nums: &[T], nan: NanPolicy) -> StatResult {
+    try_lp(nums, f64::INFINITY, Summation::default(), nan)
+}
+
+/// Minkowski distance between the points `xs` and `ys`, of
+/// the same dimension, for `p` at least 1: the
+/// [Lp norm](lp) of their difference. NaN handling skips
+/// the coordinates where either point is NaN.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let d = minkowski_distance(&[0.0, 0.0], &[1.0, 2.0], 3.0, NanPolicy::Error);
+/// assert_eq!(Ok(9f64.cbrt()), d);
+/// ```
+/// ```
+/// # use stats::*;
+/// let d = minkowski_distance(&[0.0, 0.0], &[1.0], 2.0, NanPolicy::Error);
+/// assert_eq!(Err(StatError::LengthMismatch { left: 2, right: 1 }), d);
+/// ```
+pub fn minkowski_distance<T: Numeric, U: Numeric>(
+    xs: &[T],
+    ys: &[U],
+    p: f64,
+    nan: NanPolicy,
+) -> StatResult {
+    check_norm_p(p)?;
+    nan.stat2(xs, ys, |xs, ys| {
+        let diffs = xs.iter().zip(ys).map(|(x, y)| x - y);
+        norm(diffs, p, Summation::default())
+    })
+}
+
+/// Euclidean distance between the points `xs` and `ys`, as
+/// for [`minkowski_distance`] with `p` of 2.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let d = euclidean_distance(&[1.0, 1.0], &[4.0, 5.0], NanPolicy::Error);
+/// assert_eq!(Ok(5.0), d);
+/// ```
+pub fn euclidean_distance<T: Numeric, U: Numeric>(
+    xs: &[T],
+    ys: &[U],
+    nan: NanPolicy,
+) -> StatResult {
+    minkowski_distance(xs, ys, 2.0, nan)
+}
+
+/// Manhattan (city block) distance between the points `xs`
+/// and `ys`, as for [`minkowski_distance`] with `p` of 1.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let d = manhattan_distance(&[1.0, 1.0], &[4.0, -3.0], NanPolicy::Error);
+/// assert_eq!(Ok(7.0), d);
+/// ```
+pub fn manhattan_distance<T: Numeric, U: Numeric>(
+    xs: &[T],
+    ys: &[U],
+    nan: NanPolicy,
+) -> StatResult {
+    minkowski_distance(xs, ys, 1.0, nan)
+}
+
+/// Chebyshev (chessboard) distance between the points `xs`
+/// and `ys`: the largest difference between their
+/// coordinates.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let d = chebyshev_distance(&[1.0, 1.0], &[4.0, -3.0], NanPolicy::Error);
+/// assert_eq!(Ok(4.0), d);
+/// ```
+pub fn chebyshev_distance<T: Numeric, U: Numeric>(
+    xs: &[T],
+    ys: &[U],
+    nan: NanPolicy,
+) -> StatResult {
+    minkowski_distance(xs, ys, f64::INFINITY, nan)
+}
+
+/// Cosine similarity of the vectors `xs` and `ys`: the
+/// cosine of the angle between them, from -1 to 1. The
+/// similarity is undefined if either vector is zero.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let s = cosine_similarity(&[1.0, 0.0], &[3.0, 3.0], NanPolicy::Error).unwrap();
+/// assert!((s - 0.5f64.sqrt()).abs() < 1e-15);
+/// ```
+/// ```
+/// # use stats::*;
+/// let s = cosine_similarity(&[1.0, 2.0], &[-2.0, -4.0], NanPolicy::Error);
+/// assert_eq!(Ok(-1.0), s);
+/// ```
+/// ```
+/// # use stats::*;
+/// let s = cosine_similarity(&[0.0, 0.0], &[1.0, 2.0], NanPolicy::Error);
+/// assert_eq!(Err(StatError::NonFinite), s);
+/// ```
+/// ```
+/// # use stats::*;
+/// let s = cosine_similarity::<f64, f64>(&[], &[], NanPolicy::Error);
+/// assert_eq!(Err(StatError::Empty), s);
+/// ```
+pub fn cosine_similarity<T: Numeric, U: Numeric>(xs: &[T], ys: &[U], nan: NanPolicy) -> StatResult {
+    nan.stat2(xs, ys, |xs, ys| {
+        if xs.is_empty() {
+            return Err(StatError::Empty);
+        }
+        let dot_sum = |xs: &[f64], ys: &[f64]| {
+            Summation::default().sum_iter(xs.iter().zip(ys).map(|(x, y)| x * y))
+        };
+        let norms = (dot_sum(xs, xs) * dot_sum(ys, ys)).sqrt();
+        let dot = dot_sum(xs, ys);
+        // Roundoff may push the ratio just outside [-1, 1].
+        finite(dot / norms).map(|s| s.clamp(-1.0, 1.0))
+    })
+}
+
+/// Cosine distance between the vectors `xs` and `ys`: one
+/// less their [cosine similarity](cosine_similarity), from
+/// 0 to 2.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let d = cosine_distance(&[1.0, 2.0], &[2.0, 4.0], NanPolicy::Error);
+/// assert_eq!(Ok(0.0), d);
+/// ```
+pub fn cosine_distance<T: Numeric, U: Numeric>(xs: &[T], ys: &[U], nan: NanPolicy) -> StatResult {
+    cosine_similarity(xs, ys, nan).map(|s| 1.0 - s)
+}
@@ -0,0 +1,126 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Numeric types accepted as input values.
+
+use std::borrow::Cow;
+
+/// A type of input value. Statistics are computed in `f64`,
+/// but integer values are added up exactly.
+///
+/// Every statistic taking its input values as slices
+/// accepts slices of any `Numeric` type, and the two slices
+/// of paired or two-sample statistics and of weighted
+/// statistics may be of different types. Slices of `f64`
+/// are used without copying. The exceptions work on `f64`
+/// in place or one value at a time: the `_mut` order
+/// statistics, which reorder the caller's buffer, and the
+/// streaming [`Moments`](crate::Moments),
+/// [`CoMoments`](crate::CoMoments) and
+/// [`Histogram`](crate::Histogram) accumulators, which are
+/// fed with [`to_f64`](Numeric::to_f64) values.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums: [u8; 4] = [1, 2, 3, 5];
+/// assert_eq!(Some(2.75), mean(&nums));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums: [f32; 4] = [1.0, f32::NAN, 3.0, 2.0];
+/// assert_eq!(Ok(2.0), try_median(&nums, NanPolicy::Skip));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums: [i64; 5] = [40, 10, 30, 20, 50];
+/// assert_eq!(Ok(20.0), quantile(&nums, 0.25, QuantileMethod::Linear, NanPolicy::Error));
+/// assert_eq!(150.0, sum(&nums, Summation::Naive));
+/// ```
+/// ```
+/// # use stats::*;
+/// let xs: [u64; 4] = [1, 2, 3, 4];
+/// let ys: [f32; 4] = [2.0, 4.0, 6.0, 8.0];
+/// assert_eq!(Ok(1.0), pearson(&xs, &ys, NanPolicy::Error));
+/// ```
+pub trait Numeric: Copy {
+    /// The value as an `f64`, rounded to nearest if it is
+    /// not exactly representable.
+    fn to_f64(self) -> f64;
+
+    /// Whether the value is a NaN. Only floating-point
+    /// values can be.
+    fn is_nan(self) -> bool {
+        false
+    }
+
+    /// The exact sum of `nums`, rounded to nearest, or
+    /// `None` if the type cannot be summed exactly or the
+    /// sum overflows.
+    fn exact_sum(_nums: &[Self]) -> Option<f64> {
+        None
+    }
+
+    /// `nums` as a slice of `f64`, if it already is one, so
+    /// that statistics need not copy it.
+    fn as_f64s(_nums: &[Self]) -> Option<&[f64]> {
+        None
+    }
+}
+
+/// The values of `nums` as `f64`, borrowed if they already
+/// are.
+pub(crate) fn to_f64s<T: Numeric>(nums: &[T]) -> Cow<'_, [f64]> {
+    match T::as_f64s(nums) {
+        Some(nums) => Cow::Borrowed(nums),
+        None => Cow::Owned(nums.iter().map(|x| x.to_f64()).collect()),
+    }
+}
+
+impl Numeric for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn is_nan(self) -> bool {
+        f64::is_nan(self)
+    }
+
+    fn as_f64s(nums: &[Self]) -> Option<&[f64]> {
+        Some(nums)
+    }
+}
+
+impl Numeric for f32 {
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+
+    fn is_nan(self) -> bool {
+        f32::is_nan(self)
+    }
+}
+
+/// Integer types, which are summed exactly in `i128`.
+macro_rules! integer_numeric {
+    ($($t:ty),*) => {
+        $(
+            impl Numeric for $t {
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn exact_sum(nums: &[Self]) -> Option<f64> {
+                    nums.iter()
+                        .try_fold(0i128, |acc, &x| acc.checked_add(x as i128))
+                        .map(|total| total as f64)
+                }
+            }
+        )*
+    };
+}
+
+integer_numeric!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
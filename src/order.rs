@@ -0,0 +1,117 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Order statistics in linear time by selection.
+
+use crate::error::*;
+use crate::nan::*;
+use crate::num::*;
+
+/// Compare two NaN-free values.
+pub(crate) fn cmp_f64(a: &f64, b: &f64) -> std::cmp::Ordering {
+    a.partial_cmp(b).unwrap()
+}
+
+/// Index of the median in a sorted list of `len` values,
+/// taking the value closer to the beginning to break ties.
+pub(crate) fn median_index(len: usize) -> usize {
+    len.saturating_sub(1) / 2
+}
+
+/// The `k`-th smallest of the NaN-free `nums`, counting
+/// from 0. The values are reordered.
+pub(crate) fn select(nums: &mut [f64], k: usize) -> StatResult {
+    if nums.is_empty() {
+        Err(StatError::Empty)
+    } else if k >= nums.len() {
+        Err(StatError::TooFewSamples {
+            needed: k + 1,
+            got: nums.len(),
+        })
+    } else {
+        let (_, x, _) = nums.select_nth_unstable_by(k, cmp_f64);
+        Ok(*x)
+    }
+}
+
+/// The `k`-th smallest of the input values, counting from
+/// 0. The input is copied; see [`order_statistic_mut`] to
+/// avoid the copy. Takes expected linear time.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(-1.0), order_statistic(&[3.0, -1.0, 2.0], 0, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(3.0), order_statistic(&[3.0, -1.0, 2.0], 2, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(
+///     Err(StatError::TooFewSamples { needed: 4, got: 3 }),
+///     order_statistic(&[3.0, -1.0, 2.0], 3, NanPolicy::Error),
+/// );
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Err(StatError::Empty), order_statistic::<f64>(&[], 0, NanPolicy::Error));
+/// ```
+pub fn order_statistic<T: Numeric>(nums: &[T], k: usize, nan: NanPolicy) -> StatResult {
+    nan.stat(nums, |nums| select(&mut nums.to_owned(), k))
+}
+
+/// The `k`-th smallest of the input values, counting from
+/// 0, as for [`order_statistic`]. The input is reordered in
+/// place instead of being copied: afterward, the result is
+/// at its sorted position, with no larger values before it
+/// and no smaller values after it. Under
+/// [`NanPolicy::Skip`] the NaNs are moved to the end and
+/// `k` counts only the remaining values.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let mut nums = [3.0, -1.0, 2.0, 0.0];
+/// assert_eq!(Ok(2.0), order_statistic_mut(&mut nums, 2, NanPolicy::Error));
+/// assert_eq!(2.0, nums[2]);
+/// ```
+/// ```
+/// # use stats::*;
+/// let mut nums = [std::f64::NAN, 3.0, -1.0, 2.0];
+/// assert_eq!(Ok(2.0), order_statistic_mut(&mut nums, 1, NanPolicy::Skip));
+/// assert!(nums[3].is_nan());
+/// ```
+pub fn order_statistic_mut(nums: &mut [f64], k: usize, nan: NanPolicy) -> StatResult {
+    match nan.apply_mut(nums)? {
+        Some(nums) => select(nums, k),
+        None => Ok(f64::NAN),
+    }
+}
+
+/// Median value of input values, as for
+/// [`median`](crate::median), reordering the input in place
+/// instead of copying it. Takes expected linear time.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let mut nums = [1.2, 0.0, -1.0, 1.0, 5.0, -3.0, -0.2, -0.5];
+/// assert_eq!(Ok(-0.2), median_mut(&mut nums, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Err(StatError::Empty), median_mut(&mut [], NanPolicy::Error));
+/// ```
+pub fn median_mut(nums: &mut [f64], nan: NanPolicy) -> StatResult {
+    match nan.apply_mut(nums)? {
+        Some(nums) => select(nums, median_index(nums.len())),
+        None => Ok(f64::NAN),
+    }
+}
@@ -0,0 +1,323 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Quantiles, using the sample quantile definitions of
+//! Hyndman and Fan, "Sample Quantiles in Statistical
+//! Packages", The American Statistician 50(4), 1996.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::*;
+use crate::nan::*;
+use crate::num::*;
+use crate::order::*;
+
+/// Definition of the sample quantile. The variants are the
+/// nine types of Hyndman and Fan, in order, named as in
+/// NumPy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuantileMethod {
+    /// Type 1: inverse of the empirical distribution
+    /// function.
+    InvertedCdf,
+    /// Type 2: as type 1, but averaging at
+    /// discontinuities.
+    AveragedInvertedCdf,
+    /// Type 3: nearest order statistic, ties to even.
+    ClosestObservation,
+    /// Type 4: linear interpolation of the empirical
+    /// distribution function.
+    InterpolatedInvertedCdf,
+    /// Type 5: piecewise linear with knots midway between
+    /// the values.
+    Hazen,
+    /// Type 6: linear, with `p(k) = k / (n + 1)`. Used by
+    /// Minitab and SPSS.
+    Weibull,
+    /// Type 7: linear, with `p(k) = (k - 1) / (n - 1)`. The
+    /// default of R, NumPy and spreadsheets. This is the
+    /// default.
+    #[default]
+    Linear,
+    /// Type 8: approximately median-unbiased whatever the
+    /// distribution. Recommended by Hyndman and Fan.
+    MedianUnbiased,
+    /// Type 9: approximately unbiased for normally
+    /// distributed values.
+    NormalUnbiased,
+}
+
+/// All the methods, in Hyndman and Fan type order.
+const METHODS: [QuantileMethod; 9] = [
+    QuantileMethod::InvertedCdf,
+    QuantileMethod::AveragedInvertedCdf,
+    QuantileMethod::ClosestObservation,
+    QuantileMethod::InterpolatedInvertedCdf,
+    QuantileMethod::Hazen,
+    QuantileMethod::Weibull,
+    QuantileMethod::Linear,
+    QuantileMethod::MedianUnbiased,
+    QuantileMethod::NormalUnbiased,
+];
+
+/// Names of the methods, in Hyndman and Fan type order.
+const NAMES: [&str; 9] = [
+    "inverted_cdf",
+    "averaged_inverted_cdf",
+    "closest_observation",
+    "interpolated_inverted_cdf",
+    "hazen",
+    "weibull",
+    "linear",
+    "median_unbiased",
+    "normal_unbiased",
+];
+
+/// Split `h` into integer and fractional parts, treating
+/// values within rounding error of an integer as that
+/// integer.
+fn split(h: f64) -> (f64, f64) {
+    let fuzz = 4.0 * f64::EPSILON * h.abs().max(1.0);
+    let j = (h + fuzz).floor();
+    let g = h - j;
+    if g.abs() < fuzz {
+        (j, 0.0)
+    } else {
+        (j, g)
+    }
+}
+
+impl QuantileMethod {
+    /// The method with the given Hyndman and Fan type
+    /// number, from 1 to 9.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use stats::*;
+    /// assert_eq!(Some(QuantileMethod::Linear), QuantileMethod::from_type(7));
+    /// ```
+    /// ```
+    /// # use stats::*;
+    /// assert_eq!(None, QuantileMethod::from_type(10));
+    /// ```
+    pub fn from_type(t: usize) -> Option<Self> {
+        if t >= 1 {
+            METHODS.get(t - 1).cloned()
+        } else {
+            None
+        }
+    }
+
+    /// The Hyndman and Fan type number of this method.
+    pub fn hf_type(self) -> usize {
+        METHODS.iter().position(|&m| m == self).unwrap() + 1
+    }
+
+    /// Position of the `p` quantile in a sorted list of `n`
+    /// values, as a 1-based index `j` and a weight `g`: the
+    /// quantile is `x[j] + g * (x[j + 1] - x[j])`, where
+    /// out-of-range indices refer to the nearest end.
+    fn position(self, n: usize, p: f64) -> (f64, f64) {
+        use QuantileMethod::*;
+        let np = n as f64 * p;
+        match self {
+            InvertedCdf => {
+                let (j, g) = split(np);
+                (j, if g == 0.0 { 0.0 } else { 1.0 })
+            }
+            AveragedInvertedCdf => {
+                let (j, g) = split(np);
+                (j, if g == 0.0 { 0.5 } else { 1.0 })
+            }
+            ClosestObservation => {
+                let (j, g) = split(np - 0.5);
+                let even = j % 2.0 == 0.0;
+                (j, if g == 0.0 && even { 0.0 } else { 1.0 })
+            }
+            _ => {
+                let m = match self {
+                    InterpolatedInvertedCdf => 0.0,
+                    Hazen => 0.5,
+                    Weibull => p,
+                    Linear => 1.0 - p,
+                    MedianUnbiased => (p + 1.0) / 3.0,
+                    NormalUnbiased => p / 4.0 + 3.0 / 8.0,
+                    _ => unreachable!(),
+                };
+                split(np + m)
+            }
+        }
+    }
+
+    /// 0-based indices of the values below and above the
+    /// `p` quantile of `n` values, and the weight of the
+    /// value above.
+    fn bracket(self, n: usize, p: f64) -> (usize, usize, f64) {
+        let (j, g) = self.position(n, p);
+        let clamp = |i: f64| (i.max(1.0).min(n as f64) as usize) - 1;
+        (clamp(j), clamp(j + 1.0), g)
+    }
+}
+
+impl fmt::Display for QuantileMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", NAMES[self.hf_type() - 1])
+    }
+}
+
+/// Parse a quantile method from its NumPy name, such as
+/// `linear`, or its Hyndman and Fan type number.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(QuantileMethod::Hazen), "hazen".parse());
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(QuantileMethod::MedianUnbiased), "8".parse());
+/// ```
+impl FromStr for QuantileMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(i) = NAMES.iter().position(|&n| n == s) {
+            return Ok(METHODS[i]);
+        }
+        s.parse()
+            .ok()
+            .and_then(QuantileMethod::from_type)
+            .ok_or_else(|| format!("unknown quantile method {}", s))
+    }
+}
+
+/// Interpolate between `lo` and `hi` with weight `g`.
+fn lerp(lo: f64, hi: f64, g: f64) -> f64 {
+    if g == 0.0 {
+        lo
+    } else if g == 1.0 {
+        hi
+    } else {
+        lo + g * (hi - lo)
+    }
+}
+
+/// Check that `p` is a probability.
+pub(crate) fn check_p(p: f64) -> StatResult<()> {
+    if (0.0..=1.0).contains(&p) {
+        Ok(())
+    } else {
+        Err(StatError::InvalidParameter("quantile must be in [0, 1]"))
+    }
+}
+
+/// The `p` quantile of the input values, for `p` between 0
+/// and 1, under the given definition. Takes expected linear
+/// time.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [4.0, 1.0, 3.0, 2.0];
+/// let q = quantile(&nums, 0.5, QuantileMethod::Linear, NanPolicy::Error);
+/// assert_eq!(Ok(2.5), q);
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [4.0, 1.0, 3.0, 2.0];
+/// let q = quantile(&nums, 0.5, QuantileMethod::InvertedCdf, NanPolicy::Error);
+/// assert_eq!(Ok(2.0), q);
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+/// let q = quantile(&nums, 0.9, QuantileMethod::Linear, NanPolicy::Error);
+/// assert_eq!(Ok(9.1), q);
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+/// let q = quantile(&nums, 0.9, QuantileMethod::Weibull, NanPolicy::Error);
+/// assert_eq!(Ok(9.9), q);
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, 2.0, 3.0];
+/// let q = quantile(&nums, 1.5, QuantileMethod::Linear, NanPolicy::Error);
+/// assert!(q.is_err());
+/// ```
+pub fn quantile<T: Numeric>(
+    nums: &[T],
+    p: f64,
+    method: QuantileMethod,
+    nan: NanPolicy,
+) -> StatResult {
+    check_p(p)?;
+    nan.stat(nums, |nums| {
+        if nums.is_empty() {
+            return Err(StatError::Empty);
+        }
+        let mut nums = nums.to_owned();
+        let (lo, hi, g) = method.bracket(nums.len(), p);
+        let x_lo = select(&mut nums, lo)?;
+        if g == 0.0 || hi == lo {
+            return Ok(x_lo);
+        }
+        // After selection, the value above is the smallest
+        // of those after the value below.
+        let x_hi = nums[hi..].iter().cloned().fold(f64::INFINITY, f64::min);
+        Ok(lerp(x_lo, x_hi, g))
+    })
+}
+
+/// The quantiles of the input values for each of the
+/// probabilities `ps`, as for [`quantile`]. The input is
+/// sorted only once.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums: Vec<f64> = (1..=100).map(f64::from).collect();
+/// let ps = [0.5, 0.9, 0.99];
+/// let qs = quantiles(&nums, &ps, QuantileMethod::Linear, NanPolicy::Error);
+/// assert_eq!(Ok(vec![50.5, 90.1, 99.01]), qs);
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, 2.0, 3.0, 4.0, 5.0];
+/// let ps = [0.0, 0.25, 1.0];
+/// let qs = quantiles(&nums, &ps, QuantileMethod::Hazen, NanPolicy::Error);
+/// assert_eq!(Ok(vec![1.0, 1.75, 5.0]), qs);
+/// ```
+pub fn quantiles<T: Numeric>(
+    nums: &[T],
+    ps: &[f64],
+    method: QuantileMethod,
+    nan: NanPolicy,
+) -> StatResult<Vec<f64>> {
+    for &p in ps {
+        check_p(p)?;
+    }
+    nan.stat_or(nums, vec![f64::NAN; ps.len()], |nums| {
+        if nums.is_empty() {
+            return Err(StatError::Empty);
+        }
+        let mut nums = nums.to_owned();
+        nums.sort_unstable_by(cmp_f64);
+        let qs = ps
+            .iter()
+            .map(|&p| {
+                let (lo, hi, g) = method.bracket(nums.len(), p);
+                lerp(nums[lo], nums[hi], g)
+            })
+            .collect();
+        Ok(qs)
+    })
+}
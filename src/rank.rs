@@ -0,0 +1,345 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Ranks, rank correlation and its tests.
+
+use crate::bivariate::*;
+use crate::distributions::*;
+use crate::error::*;
+use crate::hypothesis::*;
+use crate::nan::*;
+use crate::num::*;
+use crate::order::*;
+
+/// Ranks of the NaN-free `nums`, from 1, with tied values
+/// given the average of the ranks they span.
+pub(crate) fn average_ranks(nums: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..nums.len()).collect();
+    order.sort_unstable_by(|&i, &j| cmp_f64(&nums[i], &nums[j]));
+    let mut ranks = vec![0.0; nums.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let x = nums[order[start]];
+        let end = start + order[start..].iter().take_while(|&&i| nums[i] == x).count();
+        // Ranks start..end, counting from 1, average to
+        // this.
+        let rank = (start + end + 1) as f64 / 2.0;
+        for &i in &order[start..end] {
+            ranks[i] = rank;
+        }
+        start = end;
+    }
+    ranks
+}
+
+/// Ranks of input values, from 1 for the smallest, with
+/// tied values given the average of the ranks they span.
+/// Under [`NanPolicy::Skip`] the NaNs are dropped, so the
+/// result may be shorter than the input.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [30.0, 10.0, 20.0, 10.0];
+/// assert_eq!(Ok(vec![4.0, 1.5, 3.0, 1.5]), ranks(&nums, NanPolicy::Error));
+/// ```
+pub fn ranks<T: Numeric>(nums: &[T], nan: NanPolicy) -> StatResult<Vec<f64>> {
+    nan.stat_or(nums, vec![f64::NAN; nums.len()], |nums| {
+        Ok(average_ranks(nums))
+    })
+}
+
+/// Spearman's rank correlation coefficient of the paired
+/// values `xs` and `ys`: the [Pearson correlation](pearson)
+/// of their ranks, with ties given average ranks. It
+/// measures how well the relationship is described by a
+/// monotone function, from -1 to 1. The inputs must have
+/// the same length. NaN handling applies to the pairs.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let xs = [1.0, 2.0, 3.0, 4.0, 5.0];
+/// let ys = [1.0, 8.0, 27.0, 64.0, 125.0];
+/// assert_eq!(Ok(1.0), spearman(&xs, &ys, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let xs = [1.0, 2.0, 3.0, 4.0, 5.0];
+/// let ys = [5.0, 6.0, 7.0, 8.0, 7.0];
+/// let rho = spearman(&xs, &ys, NanPolicy::Error).unwrap();
+/// assert!((rho - 0.8207826816681233).abs() < 1e-15);
+/// ```
+/// ```
+/// # use stats::*;
+/// let xs = [1.0, 2.0, 3.0];
+/// let ys = [4.0, 4.0, 4.0];
+/// assert_eq!(Err(StatError::ZeroVariance), spearman(&xs, &ys, NanPolicy::Error));
+/// ```
+pub fn spearman<T: Numeric, U: Numeric>(xs: &[T], ys: &[U], nan: NanPolicy) -> StatResult {
+    nan.stat2(xs, ys, |xs, ys| {
+        pearson(&average_ranks(xs), &average_ranks(ys), NanPolicy::Error)
+    })
+}
+
+/// Lengths of the runs of equal values in the sorted
+/// `nums`.
+fn runs<T: PartialEq>(sorted: &[T]) -> Vec<u64> {
+    let mut runs = Vec::new();
+    let mut run = 1;
+    for i in 1..=sorted.len() {
+        if i < sorted.len() && sorted[i] == sorted[i - 1] {
+            run += 1;
+        } else {
+            runs.push(run);
+            run = 1;
+        }
+    }
+    runs
+}
+
+/// Number of pairs of tied values in runs of the given
+/// lengths.
+fn tied_pairs(runs: &[u64]) -> u64 {
+    runs.iter().map(|t| t * (t - 1) / 2).sum()
+}
+
+/// Sort `nums` with a merge sort, using `buf` of the same
+/// length as scratch space, and return the number of
+/// exchanges of adjacent values a bubble sort would make:
+/// the number of pairs out of order.
+fn sort_count_swaps(nums: &mut [f64], buf: &mut [f64]) -> u64 {
+    let n = nums.len();
+    if n < 2 {
+        return 0;
+    }
+    let mid = n / 2;
+    let mut swaps = {
+        let (lo, hi) = nums.split_at_mut(mid);
+        let (lo_buf, hi_buf) = buf.split_at_mut(mid);
+        sort_count_swaps(lo, lo_buf) + sort_count_swaps(hi, hi_buf)
+    };
+    let (mut i, mut j) = (0, mid);
+    for slot in buf.iter_mut() {
+        if j == n || (i < mid && nums[i] <= nums[j]) {
+            *slot = nums[i];
+            i += 1;
+        } else {
+            // Each value left in the lower half is out of
+            // order with this one.
+            swaps += (mid - i) as u64;
+            *slot = nums[j];
+            j += 1;
+        }
+    }
+    nums.copy_from_slice(buf);
+    swaps
+}
+
+/// Concordance of the NaN-free paired values, for
+/// Kendall's tau.
+struct Concordance {
+    /// Number of pairs of pairs.
+    all: u64,
+    /// Concordant less discordant pairs of pairs.
+    s: f64,
+    /// Lengths of the runs of tied values of each variable.
+    x_runs: Vec<u64>,
+    y_runs: Vec<u64>,
+}
+
+impl Concordance {
+    /// Count the concordance of `xs` and `ys` with Knight's
+    /// merge sort algorithm, taking `O(n log n)` time.
+    fn new(xs: &[f64], ys: &[f64]) -> StatResult<Self> {
+        let n = xs.len();
+        match n {
+            0 => return Err(StatError::Empty),
+            1 => return Err(StatError::TooFewSamples { needed: 2, got: 1 }),
+            _ => (),
+        }
+        let mut pairs: Vec<(f64, f64)> = xs.iter().cloned().zip(ys.iter().cloned()).collect();
+        pairs.sort_unstable_by(|a, b| cmp_f64(&a.0, &b.0).then(cmp_f64(&a.1, &b.1)));
+        let all = (n * (n - 1) / 2) as u64;
+        let x_runs = runs(&pairs.iter().map(|p| p.0).collect::<Vec<f64>>());
+        let joint_ties = tied_pairs(&runs(&pairs));
+        let mut ys: Vec<f64> = pairs.iter().map(|p| p.1).collect();
+        let swaps = sort_count_swaps(&mut ys, &mut vec![0.0; n]);
+        let y_runs = runs(&ys);
+        let (x_ties, y_ties) = (tied_pairs(&x_runs), tied_pairs(&y_runs));
+        if x_ties == all || y_ties == all {
+            return Err(StatError::ZeroVariance);
+        }
+        let s = all as f64 - x_ties as f64 - y_ties as f64 + joint_ties as f64 - 2.0 * swaps as f64;
+        Ok(Concordance {
+            all,
+            s,
+            x_runs,
+            y_runs,
+        })
+    }
+
+    /// Kendall's tau-b.
+    fn tau_b(&self) -> StatResult {
+        let x_untied = (self.all - tied_pairs(&self.x_runs)) as f64;
+        let y_untied = (self.all - tied_pairs(&self.y_runs)) as f64;
+        let tau = self.s / (x_untied.sqrt() * y_untied.sqrt());
+        finite(tau).map(|tau| tau.clamp(-1.0, 1.0))
+    }
+
+    /// Variance of `s` for independent variables, corrected
+    /// for ties.
+    fn s_variance(&self) -> f64 {
+        let n = self.x_runs.iter().sum::<u64>() as f64;
+        let sum =
+            |runs: &[u64], f: fn(f64) -> f64| -> f64 { runs.iter().map(|&t| f(t as f64)).sum() };
+        let v = |t: f64| t * (t - 1.0) * (2.0 * t + 5.0);
+        let pairs = |t: f64| t * (t - 1.0);
+        let triples = |t: f64| t * (t - 1.0) * (t - 2.0);
+        let (x, y) = (&self.x_runs[..], &self.y_runs[..]);
+        // The last term is zero, not 0/0, for two pairs.
+        (v(n) - sum(x, v) - sum(y, v)) / 18.0
+            + sum(x, pairs) * sum(y, pairs) / (2.0 * pairs(n))
+            + sum(x, triples) * sum(y, triples) / (9.0 * triples(n).max(1.0))
+    }
+}
+
+/// Kendall's tau-b rank correlation coefficient of the
+/// paired values `xs` and `ys`: the difference between the
+/// numbers of concordant and discordant pairs of pairs,
+/// normalized to run from -1 to 1 with a correction for
+/// ties. The inputs must have the same length. NaN handling
+/// applies to the pairs. Uses Knight's merge sort
+/// algorithm, taking `O(n log n)` time.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let xs = [1.0, 2.0, 3.0, 4.0];
+/// let ys = [1.0, 3.0, 2.0, 4.0];
+/// let tau = kendall_tau_b(&xs, &ys, NanPolicy::Error).unwrap();
+/// assert!((tau - 2.0 / 3.0).abs() < 1e-15);
+/// ```
+/// ```
+/// # use stats::*;
+/// let xs = [12.0, 2.0, 1.0, 12.0, 2.0];
+/// let ys = [1.0, 4.0, 7.0, 1.0, 0.0];
+/// let tau = kendall_tau_b(&xs, &ys, NanPolicy::Error).unwrap();
+/// assert!((tau + 0.4714045207910316).abs() < 1e-15);
+/// ```
+/// ```
+/// # use stats::*;
+/// let xs = [3.0, 2.0, 1.0];
+/// assert_eq!(Ok(-1.0), kendall_tau_b(&xs, &[1.0, 2.0, 3.0], NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let err = StatError::TooFewSamples { needed: 2, got: 1 };
+/// assert_eq!(Err(err), kendall_tau_b(&[1.0], &[1.0], NanPolicy::Error));
+/// ```
+pub fn kendall_tau_b<T: Numeric, U: Numeric>(xs: &[T], ys: &[U], nan: NanPolicy) -> StatResult {
+    nan.stat2(xs, ys, |xs, ys| Concordance::new(xs, ys)?.tau_b())
+}
+
+/// Result of a test of rank correlation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CorrelationTest {
+    /// The correlation coefficient.
+    pub statistic: f64,
+    /// Probability for uncorrelated variables of a
+    /// coefficient at least as extreme as that observed, in
+    /// the direction of the alternative hypothesis.
+    pub p_value: f64,
+}
+
+impl CorrelationTest {
+    /// Test result of NaNs, for NaNs propagated from the
+    /// input.
+    fn nan() -> Self {
+        CorrelationTest {
+            statistic: f64::NAN,
+            p_value: f64::NAN,
+        }
+    }
+}
+
+/// Test of [Spearman's rank correlation](spearman) of the
+/// paired values `xs` and `ys`. The p-value is from the t
+/// distribution of `rho sqrt((n - 2) / (1 - rho^2))` with
+/// `n - 2` degrees of freedom, which approximates it well
+/// for more than about ten pairs. At least three pairs are
+/// needed.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let xs = [1.0, 2.0, 3.0, 4.0, 5.0];
+/// let ys = [5.0, 6.0, 7.0, 8.0, 7.0];
+/// let test = spearman_test(&xs, &ys, Alternative::TwoSided, NanPolicy::Error).unwrap();
+/// assert!((test.statistic - 0.8207826816681233).abs() < 1e-15);
+/// assert!((test.p_value - 0.0885870053135438).abs() < 1e-14);
+/// ```
+/// ```
+/// # use stats::*;
+/// let xs = [1.0, 2.0, 3.0];
+/// let test = spearman_test(&xs, &[3.0, 2.0, 1.0], Alternative::Less, NanPolicy::Error).unwrap();
+/// assert_eq!((-1.0, 0.0), (test.statistic, test.p_value));
+/// ```
+pub fn spearman_test<T: Numeric, U: Numeric>(
+    xs: &[T],
+    ys: &[U],
+    alternative: Alternative,
+    nan: NanPolicy,
+) -> StatResult<CorrelationTest> {
+    nan.stat2_or(xs, ys, CorrelationTest::nan(), |xs, ys| {
+        let n = xs.len();
+        if n < 3 {
+            return Err(StatError::TooFewSamples { needed: 3, got: n });
+        }
+        let rho = spearman(xs, ys, NanPolicy::Error)?;
+        let df = (n - 2) as f64;
+        let t = rho * (df / ((1.0 - rho) * (1.0 + rho))).sqrt();
+        Ok(CorrelationTest {
+            statistic: rho,
+            p_value: p_value(&StudentsT::new(df)?, t, alternative),
+        })
+    })
+}
+
+/// Test of [Kendall's tau-b](kendall_tau_b) of the paired
+/// values `xs` and `ys`. The p-value is from the normal
+/// approximation to the distribution of the number of
+/// concordant less discordant pairs of pairs, with its
+/// variance corrected for ties.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let xs = [12.0, 2.0, 1.0, 12.0, 2.0];
+/// let ys = [1.0, 4.0, 7.0, 1.0, 0.0];
+/// let test = kendall_tau_b_test(&xs, &ys, Alternative::TwoSided, NanPolicy::Error).unwrap();
+/// assert!((test.statistic + 0.4714045207910316).abs() < 1e-15);
+/// assert!((test.p_value - 0.2827454599327748).abs() < 1e-14);
+/// ```
+pub fn kendall_tau_b_test<T: Numeric, U: Numeric>(
+    xs: &[T],
+    ys: &[U],
+    alternative: Alternative,
+    nan: NanPolicy,
+) -> StatResult<CorrelationTest> {
+    nan.stat2_or(xs, ys, CorrelationTest::nan(), |xs, ys| {
+        let concordance = Concordance::new(xs, ys)?;
+        let z = concordance.s / concordance.s_variance().sqrt();
+        Ok(CorrelationTest {
+            statistic: concordance.tau_b()?,
+            p_value: p_value(&Normal::standard(), z, alternative),
+        })
+    })
+}
@@ -0,0 +1,198 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Simple linear regression by ordinary least squares.
+
+use crate::distributions::*;
+use crate::error::*;
+use crate::interval::*;
+use crate::nan::*;
+use crate::num::*;
+use crate::sum::*;
+
+/// Fit of the line `y = intercept + slope * x` to paired
+/// values by ordinary least squares, with the usual
+/// diagnostics. Built by [`linear_regression`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearFit {
+    /// Number of pairs fitted.
+    pub count: usize,
+    /// Slope of the fitted line.
+    pub slope: f64,
+    /// Intercept of the fitted line.
+    pub intercept: f64,
+    /// Coefficient of determination: the fraction of the
+    /// variance of the `y` values explained by the fit.
+    pub r_squared: f64,
+    /// Residual standard error: the estimated standard
+    /// deviation of the errors, with `n - 2` degrees of
+    /// freedom.
+    pub residual_se: f64,
+    /// Standard error of the slope.
+    pub slope_se: f64,
+    /// Standard error of the intercept.
+    pub intercept_se: f64,
+}
+
+impl LinearFit {
+    /// The fitted value of `y` at `x`.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use stats::*;
+    /// let fit = linear_regression(&[0.0, 1.0, 2.0], &[1.0, 3.0, 5.0], NanPolicy::Error).unwrap();
+    /// assert_eq!(11.0, fit.predict(5.0));
+    /// ```
+    pub fn predict(&self, x: f64) -> f64 {
+        self.intercept + self.slope * x
+    }
+
+    /// Confidence interval around `estimate` with standard
+    /// error `se` and confidence `level`.
+    fn interval(&self, estimate: f64, se: f64, level: f64) -> StatResult<Interval> {
+        check_level(level)?;
+        if estimate.is_nan() || se.is_nan() {
+            return Ok(Interval::nan(level));
+        }
+        if self.count < 3 {
+            return Err(StatError::TooFewSamples {
+                needed: 3,
+                got: self.count,
+            });
+        }
+        let t = StudentsT::new((self.count - 2) as f64)?;
+        Interval::around(estimate, t.inverse_cdf(0.5 + 0.5 * level) * se, level)
+    }
+
+    /// Confidence interval for the slope, with confidence
+    /// `level` between 0 and 1, from Student's t
+    /// distribution with `n - 2` degrees of freedom.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use stats::*;
+    /// let xs = [1.0, 2.0, 3.0, 4.0, 5.0];
+    /// let ys = [2.0, 4.0, 5.0, 4.0, 5.0];
+    /// let fit = linear_regression(&xs, &ys, NanPolicy::Error).unwrap();
+    /// let ci = fit.slope_ci(0.95).unwrap();
+    /// assert!((ci.lower + 0.300132).abs() < 1e-6 && (ci.upper - 1.500132).abs() < 1e-6);
+    /// ```
+    pub fn slope_ci(&self, level: f64) -> StatResult<Interval> {
+        self.interval(self.slope, self.slope_se, level)
+    }
+
+    /// Confidence interval for the intercept, with
+    /// confidence `level` between 0 and 1, as for
+    /// [`slope_ci`](LinearFit::slope_ci).
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// # use stats::*;
+    /// let xs = [1.0, 2.0, 3.0, 4.0, 5.0];
+    /// let ys = [2.0, 4.0, 5.0, 4.0, 5.0];
+    /// let fit = linear_regression(&xs, &ys, NanPolicy::Error).unwrap();
+    /// let ci = fit.intercept_ci(0.95).unwrap();
+    /// assert!((ci.lower + 0.785399).abs() < 1e-6 && (ci.upper - 5.185399).abs() < 1e-6);
+    /// assert!(fit.intercept_ci(1.0).is_err());
+    /// ```
+    pub fn intercept_ci(&self, level: f64) -> StatResult<Interval> {
+        self.interval(self.intercept, self.intercept_se, level)
+    }
+}
+
+/// Fit a line to the paired values `xs` and `ys` by
+/// ordinary least squares, minimizing the sum of squared
+/// vertical distances from the points to the line. The
+/// inputs must have the same length, with at least three
+/// pairs so that the error can be estimated, and the `x`
+/// values must not all be equal. A perfect fit, including
+/// one to constant `y` values, has an `r_squared` of 1. NaN
+/// handling applies to the pairs.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let xs = [1.0, 2.0, 3.0, 4.0, 5.0];
+/// let ys = [2.0, 4.0, 5.0, 4.0, 5.0];
+/// let fit = linear_regression(&xs, &ys, NanPolicy::Error).unwrap();
+/// assert!((fit.slope - 0.6).abs() < 1e-15);
+/// assert!((fit.intercept - 2.2).abs() < 1e-15);
+/// assert!((fit.r_squared - 0.6).abs() < 1e-15);
+/// assert!((fit.residual_se - 0.8f64.sqrt()).abs() < 1e-15);
+/// assert!((fit.slope_se - 0.08f64.sqrt()).abs() < 1e-15);
+/// assert!((fit.intercept_se - 0.88f64.sqrt()).abs() < 1e-15);
+/// ```
+/// ```
+/// # use stats::*;
+/// let fit = linear_regression(&[1.0, 2.0, 3.0], &[7.0, 7.0, 7.0], NanPolicy::Error).unwrap();
+/// assert_eq!((0.0, 7.0, 1.0), (fit.slope, fit.intercept, fit.r_squared));
+/// ```
+/// ```
+/// # use stats::*;
+/// let fit = linear_regression(&[2.0, 2.0, 2.0], &[1.0, 2.0, 3.0], NanPolicy::Error);
+/// assert_eq!(Err(StatError::ZeroVariance), fit);
+/// ```
+/// ```
+/// # use stats::*;
+/// let err = StatError::TooFewSamples { needed: 3, got: 2 };
+/// assert_eq!(Err(err), linear_regression(&[1.0, 2.0], &[1.0, 2.0], NanPolicy::Error));
+/// ```
+pub fn linear_regression<T: Numeric, U: Numeric>(
+    xs: &[T],
+    ys: &[U],
+    nan: NanPolicy,
+) -> StatResult<LinearFit> {
+    let nan_fit = LinearFit {
+        count: xs.len(),
+        slope: f64::NAN,
+        intercept: f64::NAN,
+        r_squared: f64::NAN,
+        residual_se: f64::NAN,
+        slope_se: f64::NAN,
+        intercept_se: f64::NAN,
+    };
+    nan.stat2_or(xs, ys, nan_fit, |xs, ys| {
+        let n = xs.len();
+        match n {
+            0 => return Err(StatError::Empty),
+            1 | 2 => return Err(StatError::TooFewSamples { needed: 3, got: n }),
+            _ => (),
+        }
+        let summation = Summation::default();
+        let mean_x = summation.sum_iter(xs.iter().cloned()) / n as f64;
+        let mean_y = summation.sum_iter(ys.iter().cloned()) / n as f64;
+        let pairs = || xs.iter().zip(ys).map(|(x, y)| (x - mean_x, y - mean_y));
+        let sxx = summation.sum_iter(pairs().map(|(dx, _)| dx * dx));
+        let syy = summation.sum_iter(pairs().map(|(_, dy)| dy * dy));
+        let sxy = summation.sum_iter(pairs().map(|(dx, dy)| dx * dy));
+        if sxx == 0.0 {
+            return Err(StatError::ZeroVariance);
+        }
+        let slope = finite(sxy / sxx)?;
+        let intercept = finite(mean_y - slope * mean_x)?;
+        let ss_res = summation.sum_iter(pairs().map(|(dx, dy)| (dy - slope * dx).powi(2)));
+        let r_squared = if ss_res == 0.0 {
+            1.0
+        } else {
+            (1.0 - ss_res / syy).clamp(0.0, 1.0)
+        };
+        let residual_se = finite((ss_res / (n - 2) as f64).sqrt())?;
+        let slope_se = residual_se / sxx.sqrt();
+        let intercept_se = residual_se * (1.0 / n as f64 + mean_x * mean_x / sxx).sqrt();
+        Ok(LinearFit {
+            count: n,
+            slope,
+            intercept,
+            r_squared,
+            residual_se,
+            slope_se,
+            intercept_se: finite(intercept_se)?,
+        })
+    })
+}
@@ -0,0 +1,207 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Robust estimators of scale, which are little affected
+//! by outliers.
+
+use crate::error::*;
+use crate::nan::*;
+use crate::num::*;
+use crate::order::*;
+use crate::quantile::*;
+
+/// Factor making the MAD a consistent estimator of the
+/// standard deviation of normal data: `1 / Φ⁻¹(3/4)`.
+const MAD_NORMAL: f64 = 1.482_602_218_505_602;
+
+/// Factor making Qn consistent for normal data.
+const QN_NORMAL: f64 = 2.219_14;
+
+/// Factor making Sn consistent for normal data.
+const SN_NORMAL: f64 = 1.1926;
+
+/// Median of NaN-free values, averaging the two middle
+/// values for an even count.
+fn midpoint_median(nums: &[f64]) -> StatResult {
+    quantile(nums, 0.5, QuantileMethod::Linear, NanPolicy::Error)
+}
+
+/// Median absolute deviation of input values: the median of
+/// their absolute deviations from their median. Both
+/// medians average the two middle values for an even count,
+/// as in R. The MAD of an empty list is undefined.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(1.0), mad(&[1.0, 2.0, 3.0, 4.0, 100.0], NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(1.5), mad(&[1.0, 2.0, 4.0, 7.0], NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Err(StatError::Empty), mad::<f64>(&[], NanPolicy::Error));
+/// ```
+pub fn mad<T: Numeric>(nums: &[T], nan: NanPolicy) -> StatResult {
+    nan.stat(nums, |nums| {
+        let center = midpoint_median(nums)?;
+        let devs: Vec<f64> = nums.iter().map(|x| (x - center).abs()).collect();
+        midpoint_median(&devs)
+    })
+}
+
+/// Median absolute deviation of input values, as for
+/// [`mad`], scaled by about 1.4826 to estimate the standard
+/// deviation of normally distributed values.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let s = scaled_mad(&[1.0, 2.0, 3.0, 4.0, 100.0], NanPolicy::Error).unwrap();
+/// assert!((s - 1.4826).abs() < 1e-4);
+/// ```
+pub fn scaled_mad<T: Numeric>(nums: &[T], nan: NanPolicy) -> StatResult {
+    mad(nums, nan).map(|m| MAD_NORMAL * m)
+}
+
+/// Interquartile range of input values: the difference
+/// between their 0.75 and 0.25 quantiles under the given
+/// definition.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+/// assert_eq!(Ok(3.5), iqr(&nums, QuantileMethod::Linear, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+/// assert_eq!(Ok(4.0), iqr(&nums, QuantileMethod::InvertedCdf, NanPolicy::Error));
+/// ```
+pub fn iqr<T: Numeric>(nums: &[T], method: QuantileMethod, nan: NanPolicy) -> StatResult {
+    let qs = quantiles(nums, &[0.25, 0.75], method, nan)?;
+    Ok(qs[1] - qs[0])
+}
+
+/// Check that there are enough NaN-free values for Qn or
+/// Sn.
+fn check_pairs(nums: &[f64]) -> StatResult<()> {
+    match nums.len() {
+        0 => Err(StatError::Empty),
+        1 => Err(StatError::TooFewSamples { needed: 2, got: 1 }),
+        _ => Ok(()),
+    }
+}
+
+/// The Qn scale estimator of Rousseeuw and Croux, "Alternatives
+/// to the Median Absolute Deviation", JASA 88(424), 1993:
+/// roughly the first quartile of the distances between pairs
+/// of values, scaled to estimate the standard deviation of
+/// normally distributed values, with the small-sample
+/// corrections of Croux and Rousseeuw (1992). Unlike the MAD
+/// it does not assume a symmetric distribution, and it is
+/// more efficient. At least two values are needed. Takes
+/// quadratic time and memory.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let q = qn(&[1.0, 2.0, 3.0, 4.0, 5.0], NanPolicy::Error).unwrap();
+/// assert!((q - 2.21914 * 0.844).abs() < 1e-12);
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, 2.0, 3.0, 4.0, 5.0, 1e6];
+/// let q = qn(&nums, NanPolicy::Error).unwrap();
+/// assert!((q - 2.21914 * 0.611 * 2.0).abs() < 1e-12);
+/// ```
+/// ```
+/// # use stats::*;
+/// let err = StatError::TooFewSamples { needed: 2, got: 1 };
+/// assert_eq!(Err(err), qn(&[1.0], NanPolicy::Error));
+/// ```
+pub fn qn<T: Numeric>(nums: &[T], nan: NanPolicy) -> StatResult {
+    nan.stat(nums, |nums| {
+        check_pairs(nums)?;
+        let n = nums.len();
+        let h = n / 2 + 1;
+        let k = h * (h - 1) / 2;
+        let mut dists = Vec::with_capacity(n * (n - 1) / 2);
+        for (i, x) in nums.iter().enumerate() {
+            dists.extend(nums[i + 1..].iter().map(|y| (x - y).abs()));
+        }
+        let d = select(&mut dists, k - 1)?;
+        let correction = match n {
+            2 => 0.399,
+            3 => 0.994,
+            4 => 0.512,
+            5 => 0.844,
+            6 => 0.611,
+            7 => 0.857,
+            8 => 0.669,
+            9 => 0.872,
+            _ if n % 2 == 1 => n as f64 / (n as f64 + 1.4),
+            _ => n as f64 / (n as f64 + 3.8),
+        };
+        finite(QN_NORMAL * correction * d)
+    })
+}
+
+/// The Sn scale estimator of Rousseeuw and Croux (1993):
+/// the low median over the values of the high median of
+/// their distances to the others, scaled to estimate the
+/// standard deviation of normally distributed values, with
+/// small-sample corrections. Like [`qn`] it does not assume
+/// a symmetric distribution. At least two values are
+/// needed. Takes quadratic time.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let s = sn(&[1.0, 2.0, 3.0, 4.0, 5.0], NanPolicy::Error).unwrap();
+/// assert!((s - 1.1926 * 1.351).abs() < 1e-12);
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 1e6];
+/// let s = sn(&nums, NanPolicy::Error).unwrap();
+/// assert!((s - 1.1926 * 3.0 * 11.0 / 10.1).abs() < 1e-12);
+/// ```
+pub fn sn<T: Numeric>(nums: &[T], nan: NanPolicy) -> StatResult {
+    nan.stat(nums, |nums| {
+        check_pairs(nums)?;
+        let n = nums.len();
+        let mut dists = vec![0.0; n];
+        let mut himeds: Vec<f64> = Vec::with_capacity(n);
+        for x in nums {
+            for (d, y) in dists.iter_mut().zip(nums) {
+                *d = (x - y).abs();
+            }
+            himeds.push(select(&mut dists, n / 2)?);
+        }
+        let s = select(&mut himeds, median_index(n))?;
+        let correction = match n {
+            2 => 0.743,
+            3 => 1.851,
+            4 => 0.954,
+            5 => 1.351,
+            6 => 0.993,
+            7 => 1.198,
+            8 => 1.005,
+            9 => 1.131,
+            _ if n % 2 == 1 => n as f64 / (n as f64 - 0.9),
+            _ => 1.0,
+        };
+        finite(SN_NORMAL * correction * s)
+    })
+}
@@ -0,0 +1,228 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Central moments and the shape statistics built from
+//! them: skewness and kurtosis.
+
+use crate::error::*;
+use crate::nan::*;
+use crate::num::*;
+use crate::sum::*;
+use crate::summation_nth_power;
+
+/// Whether a shape statistic is corrected for small-sample
+/// bias.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Bias {
+    /// Use the moments of the values themselves: the
+    /// skewness `g1 = m3 / m2^(3/2)` and the kurtosis
+    /// `b2 = m4 / m2^2`, where `mk` is the `k`-th central
+    /// moment.
+    Biased,
+    /// Use the usual bias-corrected estimators of the
+    /// population statistic from a sample: the adjusted
+    /// Fisher–Pearson skewness `G1` and the kurtosis
+    /// `G2 + 3`, as reported by spreadsheets, SAS and
+    /// pandas. This is the default.
+    #[default]
+    Corrected,
+}
+
+/// Skewness of `n` values from the sums of their squared
+/// and cubed deviations from the mean.
+pub(crate) fn skewness_from(n: f64, s2: f64, s3: f64, bias: Bias) -> StatResult {
+    let needed = match bias {
+        Bias::Biased => 1,
+        Bias::Corrected => 3,
+    };
+    check_shape(n, s2, needed)?;
+    let g1 = (s3 / n) / (s2 / n).powf(1.5);
+    match bias {
+        Bias::Biased => finite(g1),
+        Bias::Corrected => finite(g1 * (n * (n - 1.0)).sqrt() / (n - 2.0)),
+    }
+}
+
+/// Kurtosis of `n` values from the sums of their squared
+/// and fourth-power deviations from the mean.
+pub(crate) fn kurtosis_from(n: f64, s2: f64, s4: f64, bias: Bias) -> StatResult {
+    let needed = match bias {
+        Bias::Biased => 1,
+        Bias::Corrected => 4,
+    };
+    check_shape(n, s2, needed)?;
+    let b2 = (s4 / n) / ((s2 / n) * (s2 / n));
+    match bias {
+        Bias::Biased => finite(b2),
+        Bias::Corrected => {
+            let g2 = ((n + 1.0) * (b2 - 3.0) + 6.0) * (n - 1.0) / ((n - 2.0) * (n - 3.0));
+            finite(g2 + 3.0)
+        }
+    }
+}
+
+/// Check that a shape statistic is defined for `n` values
+/// with sum of squared deviations `s2`.
+fn check_shape(n: f64, s2: f64, needed: usize) -> StatResult<()> {
+    if n == 0.0 {
+        Err(StatError::Empty)
+    } else if n < needed as f64 {
+        Err(StatError::TooFewSamples {
+            needed,
+            got: n as usize,
+        })
+    } else if s2 == 0.0 {
+        Err(StatError::ZeroVariance)
+    } else {
+        Ok(())
+    }
+}
+
+/// Sum of the `k`-th powers of the deviations of NaN-free
+/// values from their mean.
+fn sum_pow_dev(nums: &[f64], k: i32) -> f64 {
+    // Roundoff in the mean of equal values would leave
+    // spurious deviations.
+    let mean = if nums.iter().all(|&x| x == nums[0]) {
+        nums[0]
+    } else {
+        sum(nums, Summation::default()) / nums.len() as f64
+    };
+    summation_nth_power(nums, mean, k)
+}
+
+/// The `k`-th central moment of input values: the mean of
+/// the `k`-th powers of their deviations from their mean.
+/// The second central moment is the population variance.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+/// assert_eq!(Ok(4.0), central_moment(&nums, 2, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+/// assert_eq!(Ok(5.25), central_moment(&nums, 3, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(1.0), central_moment(&[3.0], 0, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Err(StatError::Empty), central_moment::<f64>(&[], 2, NanPolicy::Error));
+/// ```
+pub fn central_moment<T: Numeric>(nums: &[T], k: u32, nan: NanPolicy) -> StatResult {
+    nan.stat(nums, |nums| {
+        if nums.is_empty() {
+            return Err(StatError::Empty);
+        }
+        let k = k.min(i32::MAX as u32) as i32;
+        finite(sum_pow_dev(nums, k) / nums.len() as f64)
+    })
+}
+
+/// Skewness of input values: a measure of the asymmetry of
+/// their distribution, positive when the upper tail is
+/// longer. The skewness of values that are all equal is
+/// undefined.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+/// assert_eq!(Ok(0.65625), skewness(&nums, Bias::Biased, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+/// let g1 = skewness(&nums, Bias::Corrected, NanPolicy::Error).unwrap();
+/// assert!((g1 - 0.8184875533567997).abs() < 1e-15);
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(0.0), skewness(&[1.0, 2.0, 3.0], Bias::Corrected, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(
+///     Err(StatError::TooFewSamples { needed: 3, got: 2 }),
+///     skewness(&[1.0, 2.0], Bias::Corrected, NanPolicy::Error),
+/// );
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [0.1, 0.1, 0.1];
+/// assert_eq!(Err(StatError::ZeroVariance), skewness(&nums, Bias::Biased, NanPolicy::Error));
+/// ```
+pub fn skewness<T: Numeric>(nums: &[T], bias: Bias, nan: NanPolicy) -> StatResult {
+    nan.stat(nums, |nums| {
+        if nums.is_empty() {
+            return Err(StatError::Empty);
+        }
+        let n = nums.len() as f64;
+        skewness_from(n, sum_pow_dev(nums, 2), sum_pow_dev(nums, 3), bias)
+    })
+}
+
+/// Kurtosis of input values: a measure of the weight of
+/// the tails of their distribution, 3 for a normal
+/// distribution. The kurtosis of values that are all equal
+/// is undefined.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+/// assert_eq!(Ok(2.78125), kurtosis(&nums, Bias::Biased, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, 2.0, 3.0, 4.0];
+/// let b2 = kurtosis(&nums, Bias::Corrected, NanPolicy::Error).unwrap();
+/// assert!((b2 - 1.8).abs() < 1e-12);
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(
+///     Err(StatError::TooFewSamples { needed: 4, got: 3 }),
+///     kurtosis(&[1.0, 2.0, 3.0], Bias::Corrected, NanPolicy::Error),
+/// );
+/// ```
+pub fn kurtosis<T: Numeric>(nums: &[T], bias: Bias, nan: NanPolicy) -> StatResult {
+    nan.stat(nums, |nums| {
+        if nums.is_empty() {
+            return Err(StatError::Empty);
+        }
+        let n = nums.len() as f64;
+        kurtosis_from(n, sum_pow_dev(nums, 2), sum_pow_dev(nums, 4), bias)
+    })
+}
+
+/// Excess kurtosis of input values: the
+/// [`kurtosis`] less 3, so that it is 0 for a normal
+/// distribution.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+/// assert_eq!(Ok(-0.21875), excess_kurtosis(&nums, Bias::Biased, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, 2.0, 3.0, 4.0];
+/// let g2 = excess_kurtosis(&nums, Bias::Corrected, NanPolicy::Error).unwrap();
+/// assert!((g2 + 1.2).abs() < 1e-12);
+/// ```
+pub fn excess_kurtosis<T: Numeric>(nums: &[T], bias: Bias, nan: NanPolicy) -> StatResult {
+    kurtosis(nums, bias, nan).map(|b2| b2 - 3.0)
+}
@@ -0,0 +1,424 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Summation strategies trading speed for accuracy.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::num::*;
+
+/// How to add up floating-point numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Summation {
+    /// Add the values in order. Fastest, but the rounding
+    /// error grows with the number of values.
+    Naive,
+    /// Kahan–Babuška–Neumaier compensated summation. The
+    /// rounding error is bounded independently of the number
+    /// of values. This is the default.
+    #[default]
+    Neumaier,
+    /// Add pairs of partial sums. The rounding error grows
+    /// only logarithmically with the number of values.
+    Pairwise,
+    /// Shewchuk's exact summation with expansions, as in
+    /// Python's `math.fsum`. The result is the correctly
+    /// rounded exact sum, independent of the order of the
+    /// values, even if an intermediate sum overflows.
+    Exact,
+}
+
+/// Number of values added naively to make a block for
+/// pairwise summation.
+const PAIRWISE_BLOCK: usize = 128;
+
+/// Half the unit in which exact summation carries
+/// overflowed intermediate sums: 2^1023.
+const HALF_CARRY: f64 = 8.98846567431158e307;
+
+/// State of a sum in progress.
+#[derive(Debug, Clone)]
+enum State {
+    Naive(f64),
+    Neumaier {
+        sum: f64,
+        comp: f64,
+    },
+    Pairwise {
+        block: f64,
+        block_len: usize,
+        /// Sums of runs of blocks with their lengths in
+        /// blocks: decreasing powers of two.
+        stack: Vec<(f64, usize)>,
+    },
+    Exact {
+        /// Nonoverlapping partial sums in increasing order
+        /// of magnitude.
+        partials: Vec<f64>,
+        /// Multiples of 2^1024 split off overflowed
+        /// intermediate sums, which belong to the total.
+        carry: i64,
+        /// Sum of the infinities and NaNs in the input.
+        special: f64,
+    },
+}
+
+/// Streaming accumulator for a sum under a given
+/// [`Summation`] strategy. Pairwise summation needs memory
+/// logarithmic in the number of values; exact summation
+/// needs memory proportional to the spread of their
+/// exponents; the others need constant memory.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let mut acc = SumAccumulator::new(Summation::Exact);
+/// acc.extend(&[1e100, 1.0, -1e100]);
+/// assert_eq!(1.0, acc.sum());
+/// ```
+/// ```
+/// # use stats::*;
+/// let acc: SumAccumulator = [0.5, 0.25].iter().collect();
+/// assert_eq!(0.75, acc.sum());
+/// ```
+#[derive(Debug, Clone)]
+pub struct SumAccumulator {
+    state: State,
+}
+
+impl Default for SumAccumulator {
+    fn default() -> Self {
+        Self::new(Summation::default())
+    }
+}
+
+impl SumAccumulator {
+    /// Make a new empty sum using the given strategy.
+    pub fn new(summation: Summation) -> Self {
+        let state = match summation {
+            Summation::Naive => State::Naive(0.0),
+            Summation::Neumaier => State::Neumaier {
+                sum: 0.0,
+                comp: 0.0,
+            },
+            Summation::Pairwise => State::Pairwise {
+                block: 0.0,
+                block_len: 0,
+                stack: Vec::new(),
+            },
+            Summation::Exact => State::Exact {
+                partials: Vec::new(),
+                carry: 0,
+                special: 0.0,
+            },
+        };
+        SumAccumulator { state }
+    }
+
+    /// Add a value to the sum.
+    pub fn push(&mut self, x: f64) {
+        match &mut self.state {
+            State::Naive(sum) => *sum += x,
+            State::Neumaier { sum, comp } => {
+                let t = *sum + x;
+                if sum.abs() >= x.abs() {
+                    *comp += (*sum - t) + x;
+                } else {
+                    *comp += (x - t) + *sum;
+                }
+                *sum = t;
+            }
+            State::Pairwise {
+                block,
+                block_len,
+                stack,
+            } => {
+                *block += x;
+                *block_len += 1;
+                if *block_len == PAIRWISE_BLOCK {
+                    let mut run = (*block, 1);
+                    while let Some(&(sum, len)) = stack.last() {
+                        if len != run.1 {
+                            break;
+                        }
+                        stack.pop();
+                        run = (sum + run.0, 2 * len);
+                    }
+                    stack.push(run);
+                    *block = 0.0;
+                    *block_len = 0;
+                }
+            }
+            State::Exact {
+                partials,
+                carry,
+                special,
+            } => {
+                if x.is_finite() {
+                    *carry += add_partial(partials, x);
+                } else {
+                    *special += x;
+                }
+            }
+        }
+    }
+
+    /// The sum of the values pushed so far. The sum of no
+    /// values is 0.0.
+    pub fn sum(&self) -> f64 {
+        match &self.state {
+            State::Naive(sum) => *sum,
+            State::Neumaier { sum, comp } => {
+                if sum.is_finite() {
+                    sum + comp
+                } else {
+                    *sum
+                }
+            }
+            State::Pairwise { block, stack, .. } => {
+                stack.iter().rev().fold(*block, |acc, &(sum, _)| sum + acc)
+            }
+            State::Exact {
+                partials,
+                carry,
+                special,
+            } => {
+                if *special != 0.0 || special.is_nan() {
+                    return *special;
+                }
+                if *carry == 0 {
+                    round_partials(partials)
+                } else {
+                    round_carried(partials, *carry)
+                }
+            }
+        }
+    }
+}
+
+/// Add the finite `x` to the nonoverlapping partial sums
+/// `partials`, given in increasing order of magnitude,
+/// keeping them so. Gives the multiple of 2^1024 split off
+/// any intermediate sum that overflows, which leaves the
+/// partials finite.
+fn add_partial(partials: &mut Vec<f64>, x: f64) -> i64 {
+    let mut carry = 0;
+    let mut x = x;
+    let mut i = 0;
+    for j in 0..partials.len() {
+        let mut y = partials[j];
+        if x.abs() < y.abs() {
+            std::mem::swap(&mut x, &mut y);
+        }
+        let mut hi = x + y;
+        let lo;
+        if hi.is_finite() {
+            lo = y - (hi - x);
+        } else {
+            // Here x and y share a sign and x is at least
+            // 2^1023, so the halves are exact and their sum
+            // is at least 2^1023, from which 2^1023 can be
+            // taken exactly.
+            let sign = x.signum();
+            let (x, y) = (0.5 * x, 0.5 * y);
+            let half = x + y;
+            hi = 2.0 * (half - sign * HALF_CARRY);
+            lo = 2.0 * (y - (half - x));
+            carry += sign as i64;
+        }
+        if lo != 0.0 {
+            partials[i] = lo;
+            i += 1;
+        }
+        x = hi;
+    }
+    partials.truncate(i);
+    partials.push(x);
+    carry
+}
+
+/// Correctly round `carry` times 2^1024 plus the sum of
+/// nonoverlapping partial sums given in increasing order of
+/// magnitude.
+fn round_carried(partials: &[f64], carry: i64) -> f64 {
+    let overflow = carry as f64 * f64::INFINITY;
+    // The partials add up to less than 2^1024, so two or more
+    // carries overflow the total.
+    if carry.abs() > 1 {
+        return overflow;
+    }
+    let half = carry as f64 * HALF_CARRY;
+    let mut carried = partials.to_vec();
+    if add_partial(&mut carried, half) == 0 && add_partial(&mut carried, half) == 0 {
+        return round_partials(&carried);
+    }
+    // An intermediate sum overflowed again, so the total is
+    // at least about 2^1022. Round half of it instead, where
+    // the carry fits. Halving is exact but for the tiniest
+    // partials, which are far below the rounding and only
+    // count through their sign, kept by one tiny stand-in.
+    let tiny = 2f64.powi(-900);
+    let mut halved: Vec<f64> = partials
+        .iter()
+        .rev()
+        .find(|p| **p != 0.0 && p.abs() < tiny)
+        .map(|p| p.signum() * 2f64.powi(-1000))
+        .into_iter()
+        .collect();
+    halved.extend(partials.iter().filter(|p| p.abs() >= tiny).map(|p| 0.5 * p));
+    if add_partial(&mut halved, half) != 0 {
+        return overflow;
+    }
+    2.0 * round_partials(&halved)
+}
+
+/// Correctly round the sum of nonoverlapping partial sums
+/// given in increasing order of magnitude.
+fn round_partials(partials: &[f64]) -> f64 {
+    let mut n = partials.len();
+    if n == 0 {
+        return 0.0;
+    }
+    n -= 1;
+    let mut hi = partials[n];
+    let mut lo = 0.0;
+    while n > 0 {
+        n -= 1;
+        let x = hi;
+        let y = partials[n];
+        hi = x + y;
+        lo = y - (hi - x);
+        if lo != 0.0 {
+            break;
+        }
+    }
+    // Round half to even if the remaining partials push the
+    // result past a halfway case.
+    if n > 0 && ((lo < 0.0 && partials[n - 1] < 0.0) || (lo > 0.0 && partials[n - 1] > 0.0)) {
+        let y = lo * 2.0;
+        let x = hi + y;
+        if y == x - hi {
+            hi = x;
+        }
+    }
+    hi
+}
+
+impl Extend<f64> for SumAccumulator {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl<'a> Extend<&'a f64> for SumAccumulator {
+    fn extend<I: IntoIterator<Item = &'a f64>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
+impl std::iter::FromIterator<f64> for SumAccumulator {
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        let mut acc = SumAccumulator::default();
+        acc.extend(iter);
+        acc
+    }
+}
+
+impl<'a> std::iter::FromIterator<&'a f64> for SumAccumulator {
+    fn from_iter<I: IntoIterator<Item = &'a f64>>(iter: I) -> Self {
+        iter.into_iter().cloned().collect()
+    }
+}
+
+impl Summation {
+    /// Sum the values produced by `iter` using this
+    /// strategy.
+    pub fn sum_iter<I: IntoIterator<Item = f64>>(self, iter: I) -> f64 {
+        let mut acc = SumAccumulator::new(self);
+        acc.extend(iter);
+        acc.sum()
+    }
+}
+
+impl fmt::Display for Summation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Summation::Naive => write!(f, "naive"),
+            Summation::Neumaier => write!(f, "neumaier"),
+            Summation::Pairwise => write!(f, "pairwise"),
+            Summation::Exact => write!(f, "exact"),
+        }
+    }
+}
+
+/// Parse a summation strategy from its name: `naive`,
+/// `neumaier` (or `kahan`), `pairwise` or `exact`.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(Summation::Exact), "exact".parse());
+/// ```
+impl FromStr for Summation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "naive" => Ok(Summation::Naive),
+            "neumaier" | "kahan" => Ok(Summation::Neumaier),
+            "pairwise" => Ok(Summation::Pairwise),
+            "exact" => Ok(Summation::Exact),
+            _ => Err(format!("unknown summation {}", s)),
+        }
+    }
+}
+
+/// Sum of input values using the given strategy. Integer
+/// values are always summed exactly. The sum of an empty
+/// list is 0.0.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(0.0, sum::<f64>(&[], Summation::Exact));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(0.0, sum(&[1e100, 1.0, -1e100], Summation::Naive));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(1.0, sum(&[1e100, 1.0, -1e100], Summation::Neumaier));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = vec![0.1; 10_000];
+/// let naive = sum(&nums, Summation::Naive);
+/// let pairwise = sum(&nums, Summation::Pairwise);
+/// assert!((pairwise - 1000.0).abs() < (naive - 1000.0).abs());
+/// assert_eq!(1000.0, sum(&nums, Summation::Neumaier));
+/// assert_eq!(1000.0, sum(&nums, Summation::Exact));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(1e-100, sum(&[1.0, 1e100, 1e-100, -1e100, -1.0], Summation::Exact));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(1e308, sum(&[1e308, 1e308, -1e308], Summation::Exact));
+/// let max = std::f64::MAX;
+/// assert_eq!(5e-324, sum(&[max, max, 5e-324, -max, -max], Summation::Exact));
+/// assert_eq!(std::f64::INFINITY, sum(&[1e308, 1e308], Summation::Exact));
+/// assert_eq!(-std::f64::INFINITY, sum(&[-max, -max, -max, max], Summation::Exact));
+/// ```
+pub fn sum<T: Numeric>(nums: &[T], summation: Summation) -> f64 {
+    T::exact_sum(nums).unwrap_or_else(|| summation.sum_iter(nums.iter().map(|x| x.to_f64())))
+}
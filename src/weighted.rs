@@ -0,0 +1,323 @@
+// Copyright © 2019 Bart Massey
+// [This program is licensed under the "MIT License"]
+// Please see the file LICENSE in the source
+// distribution of this software for license terms.
+
+//! Statistics of weighted values. Each value in `nums` has
+//! the nonnegative weight at the same position in
+//! `weights`; NaN handling applies to the value and weight
+//! together.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::*;
+use crate::nan::*;
+use crate::num::*;
+use crate::order::*;
+use crate::quantile::*;
+use crate::sum::*;
+
+/// What weights mean, which determines how the variance is
+/// corrected for bias.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeightKind {
+    /// Weights count repeated values, as for bucketed data.
+    /// The result is the same as for the list with each
+    /// value repeated. This is the default.
+    #[default]
+    Frequency,
+    /// Weights give the relative reliability of each value,
+    /// as for survey samples. Scaling all the weights by
+    /// the same factor does not change the result.
+    Reliability,
+}
+
+impl fmt::Display for WeightKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WeightKind::Frequency => write!(f, "frequency"),
+            WeightKind::Reliability => write!(f, "reliability"),
+        }
+    }
+}
+
+/// Parse a weight kind from its name: `frequency` or
+/// `reliability`.
+impl FromStr for WeightKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "frequency" => Ok(WeightKind::Frequency),
+            "reliability" => Ok(WeightKind::Reliability),
+            _ => Err(format!("unknown weight kind {}", s)),
+        }
+    }
+}
+
+/// Check that NaN-free weights are finite and nonnegative,
+/// returning their total, which must be positive.
+fn total_weight(weights: &[f64]) -> StatResult {
+    if weights.iter().any(|&w| !w.is_finite() || w < 0.0) {
+        return Err(StatError::InvalidParameter(
+            "weights must be finite and nonnegative",
+        ));
+    }
+    let total = sum(weights, Summation::default());
+    if total == 0.0 {
+        Err(StatError::Empty)
+    } else {
+        finite(total)
+    }
+}
+
+/// Weighted sum of NaN-free values, ignoring values of
+/// zero weight.
+fn weighted_sum<F>(nums: &[f64], weights: &[f64], f: F) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    let terms = nums
+        .iter()
+        .zip(weights)
+        .filter(|(_, &w)| w > 0.0)
+        .map(|(&x, &w)| w * f(x));
+    Summation::default().sum_iter(terms)
+}
+
+/// Weighted arithmetic mean of input values. The total
+/// weight must be positive.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(1.25), weighted_mean(&[1.0, 2.0, 3.0], &[3.0, 1.0, 0.0], NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(Err(StatError::Empty), weighted_mean(&[1.0, 2.0], &[0.0, 0.0], NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// assert_eq!(
+///     Err(StatError::LengthMismatch { left: 2, right: 1 }),
+///     weighted_mean(&[1.0, 2.0], &[1.0], NanPolicy::Error),
+/// );
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, std::f64::NAN, 4.0];
+/// let weights = [1.0, 5.0, 2.0];
+/// assert_eq!(Ok(3.0), weighted_mean(&nums, &weights, NanPolicy::Skip));
+/// ```
+pub fn weighted_mean<T: Numeric, U: Numeric>(
+    nums: &[T],
+    weights: &[U],
+    nan: NanPolicy,
+) -> StatResult {
+    nan.stat2(nums, weights, |nums, weights| {
+        let total = total_weight(weights)?;
+        finite(weighted_sum(nums, weights, |x| x) / total)
+    })
+}
+
+/// Weighted variance of input values with `ddof` delta
+/// degrees of freedom, as for [`variance`](crate::variance).
+/// For [frequency](WeightKind::Frequency) weights the sum
+/// of weighted squared deviations is divided by `W - ddof`,
+/// where `W` is the total weight. For
+/// [reliability](WeightKind::Reliability) weights it is
+/// divided by `W - ddof * V / W`, where `V` is the sum of
+/// the squared weights.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, 2.0, 3.0];
+/// let weights = [2.0, 1.0, 1.0];
+/// let v = weighted_variance(&nums, &weights, WeightKind::Frequency, 0, NanPolicy::Error);
+/// assert_eq!(variance(&[1.0, 1.0, 2.0, 3.0], 0), v.ok());
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, 2.0, 3.0];
+/// let weights = [2.0, 1.0, 1.0];
+/// let v = weighted_variance(&nums, &weights, WeightKind::Frequency, 1, NanPolicy::Error);
+/// assert_eq!(variance(&[1.0, 1.0, 2.0, 3.0], 1), v.ok());
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1.0, 1.0, 2.0, 3.0];
+/// let weights = [2.0, 2.0, 2.0, 2.0];
+/// let v = weighted_variance(&nums, &weights, WeightKind::Reliability, 1, NanPolicy::Error);
+/// assert_eq!(variance(&nums, 1), v.ok());
+/// ```
+/// ```
+/// # use stats::*;
+/// let v = weighted_variance(&[1.0, 2.0], &[3.0, 0.0], WeightKind::Reliability, 1, NanPolicy::Error);
+/// assert_eq!(Err(StatError::TooFewSamples { needed: 2, got: 1 }), v);
+/// ```
+pub fn weighted_variance<T: Numeric, U: Numeric>(
+    nums: &[T],
+    weights: &[U],
+    kind: WeightKind,
+    ddof: usize,
+    nan: NanPolicy,
+) -> StatResult {
+    nan.stat2(nums, weights, |nums, weights| {
+        let total = total_weight(weights)?;
+        let mean = weighted_sum(nums, weights, |x| x) / total;
+        let sum_sq_dev = weighted_sum(nums, weights, |x| (x - mean) * (x - mean));
+        let ddof_f = ddof as f64;
+        let dof = match kind {
+            WeightKind::Frequency => {
+                let dof = total - ddof_f;
+                if dof <= 0.0 {
+                    return Err(StatError::TooFewSamples {
+                        needed: ddof + 1,
+                        got: total as usize,
+                    });
+                }
+                dof
+            }
+            WeightKind::Reliability => {
+                let sum_sq_weights = Summation::default().sum_iter(weights.iter().map(|w| w * w));
+                let dof = total - ddof_f * sum_sq_weights / total;
+                // Roundoff may leave a tiny positive `dof` for
+                // too few values, so count them instead.
+                let got = weights.iter().filter(|&&w| w > 0.0).count();
+                if got <= ddof || dof <= 0.0 {
+                    return Err(StatError::TooFewSamples {
+                        needed: ddof + 1,
+                        got,
+                    });
+                }
+                dof
+            }
+        };
+        finite(sum_sq_dev / dof)
+    })
+}
+
+/// NaN-free values of positive weight in increasing order,
+/// with the cumulative weight up to and including each.
+fn cumulative(nums: &[f64], weights: &[f64]) -> StatResult<(Vec<f64>, Vec<f64>)> {
+    total_weight(weights)?;
+    let mut pairs: Vec<(f64, f64)> = nums
+        .iter()
+        .cloned()
+        .zip(weights.iter().cloned())
+        .filter(|&(_, w)| w > 0.0)
+        .collect();
+    pairs.sort_unstable_by(|a, b| cmp_f64(&a.0, &b.0));
+    let mut acc = SumAccumulator::default();
+    let (nums, cum) = pairs
+        .into_iter()
+        .map(|(x, w)| {
+            acc.push(w);
+            (x, acc.sum())
+        })
+        .unzip();
+    Ok((nums, cum))
+}
+
+/// The smallest of the sorted `nums` whose cumulative
+/// weight reaches the fraction `p` of the total.
+fn weighted_rank(nums: &[f64], cum: &[f64], p: f64) -> f64 {
+    let total = cum[cum.len() - 1];
+    let target = p * total - 4.0 * f64::EPSILON * total;
+    let k = cum.partition_point(|&c| c < target);
+    nums[k.min(nums.len() - 1)]
+}
+
+/// Weighted quantiles of input values for each of the
+/// probabilities `ps`, as for [`weighted_quantile`]. The
+/// input is sorted only once.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [10.0, 20.0, 30.0, 40.0];
+/// let weights = [1.0, 2.0, 3.0, 4.0];
+/// let qs = weighted_quantiles(&nums, &weights, &[0.0, 0.25, 0.5, 1.0], NanPolicy::Error);
+/// assert_eq!(Ok(vec![10.0, 20.0, 30.0, 40.0]), qs);
+/// ```
+pub fn weighted_quantiles<T: Numeric, U: Numeric>(
+    nums: &[T],
+    weights: &[U],
+    ps: &[f64],
+    nan: NanPolicy,
+) -> StatResult<Vec<f64>> {
+    for &p in ps {
+        check_p(p)?;
+    }
+    nan.stat2_or(nums, weights, vec![f64::NAN; ps.len()], |nums, weights| {
+        let (nums, cum) = cumulative(nums, weights)?;
+        Ok(ps.iter().map(|&p| weighted_rank(&nums, &cum, p)).collect())
+    })
+}
+
+/// Weighted `p` quantile of input values, for `p` between
+/// 0 and 1: the smallest value such that the values no
+/// larger than it carry at least the fraction `p` of the
+/// total weight. With all weights equal to one this is the
+/// [inverted CDF](crate::QuantileMethod::InvertedCdf)
+/// quantile.
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// let nums = [10.0, 20.0, 30.0, 40.0];
+/// let weights = [1.0, 2.0, 3.0, 4.0];
+/// assert_eq!(Ok(40.0), weighted_quantile(&nums, &weights, 0.9, NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [4.0, 1.0, 3.0, 2.0];
+/// let weights = [1.0; 4];
+/// let q = quantile(&nums, 0.3, QuantileMethod::InvertedCdf, NanPolicy::Error);
+/// assert_eq!(q, weighted_quantile(&nums, &weights, 0.3, NanPolicy::Error));
+/// ```
+pub fn weighted_quantile<T: Numeric, U: Numeric>(
+    nums: &[T],
+    weights: &[U],
+    p: f64,
+    nan: NanPolicy,
+) -> StatResult {
+    weighted_quantiles(nums, weights, &[p], nan).map(|qs| qs[0])
+}
+
+/// Weighted median of input values: the smallest value
+/// such that the values no larger than it carry at least
+/// half the total weight. With all weights equal to one
+/// this is the same as [`median`](crate::median).
+///
+/// # Examples:
+///
+/// ```
+/// # use stats::*;
+/// assert_eq!(Ok(3.0), weighted_median(&[1.0, 2.0, 3.0], &[1.0, 1.0, 3.0], NanPolicy::Error));
+/// ```
+/// ```
+/// # use stats::*;
+/// let nums = [1.2, 0.0, -1.0, 1.0, 5.0, -3.0];
+/// let weights = [1.0; 6];
+/// assert_eq!(median(&nums), weighted_median(&nums, &weights, NanPolicy::Error).ok());
+/// ```
+/// ```
+/// # use stats::*;
+/// let weights = [1.0, -1.0];
+/// assert!(weighted_median(&[1.0, 2.0], &weights, NanPolicy::Error).is_err());
+/// ```
+pub fn weighted_median<T: Numeric, U: Numeric>(
+    nums: &[T],
+    weights: &[U],
+    nan: NanPolicy,
+) -> StatResult {
+    weighted_quantile(nums, weights, 0.5, nan)
+}